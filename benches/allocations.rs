@@ -0,0 +1,114 @@
+//! Counts the heap allocations made by the insertion paths of
+//! `UidStore`, and compares two ways to insert a borrowed UID into a
+//! bare `HashSet`: copying it up front, or checking for it first. Run
+//! with `cargo bench --bench allocations`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use uid_store::UidStore;
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+// Run `f` `count` times, reporting the allocations and time per call.
+fn measure<F: FnMut()>(name: &str, count: usize, mut f: F) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..count {
+        f();
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "{:<36} {:>8.3} allocations/call {:>10.1?}/call",
+        name,
+        allocations as f64 / count as f64,
+        elapsed / count as u32
+    );
+}
+
+// Insert `uid` by copying it first, which hashes once but allocates
+// even when `uid` is already in use.
+fn insert_copied(items: &mut HashSet<String>, uid: &str) -> bool {
+    items.insert(uid.to_string())
+}
+
+// Insert `uid` only after checking it is missing, as `UidStore` does,
+// which hashes twice on a miss but never allocates on a hit.
+fn insert_checked(items: &mut HashSet<String>, uid: &str) -> bool {
+    if items.contains(uid) {
+        return false;
+    }
+    items.insert(uid.to_string())
+}
+
+// Measure `insert` for UID's that are already in use, then for UID's
+// that are not.
+fn compare(name: &str, count: usize, insert: fn(&mut HashSet<String>, &str) -> bool) {
+    let ids: Vec<String> = UidStore::new().next_many(count, 12);
+    let mut items: HashSet<String> = ids.iter().cloned().collect();
+    let mut hits = ids.iter().cycle();
+    measure(&format!("{}, already in use", name), count, || {
+        assert!(!insert(&mut items, hits.next().unwrap()));
+    });
+
+    let mut items = HashSet::with_capacity(count);
+    let mut misses = ids.iter();
+    measure(&format!("{}, unused", name), count, || {
+        assert!(insert(&mut items, misses.next().unwrap()));
+    });
+}
+
+fn main() {
+    let count = 100_000;
+
+    compare("copy then insert", count, insert_copied);
+    compare("check then insert", count, insert_checked);
+
+    let mut u = UidStore::with_capacity(count);
+    measure("next(12), empty keyspace", count, || {
+        u.next(12);
+    });
+
+    let mut u = UidStore::new();
+    let id = u.next(12).clone();
+    measure("commit, already in use", count, || {
+        assert!(!u.commit(&id));
+    });
+
+    // With 3800 of 3844 two character UID's in use, most attempts
+    // collide, so the cost of a collision dominates.
+    let mut u = UidStore::with_capacity(3844);
+    u.next_many(3800, 2);
+    measure("next(2), nearly full keyspace", 40, || {
+        u.next(2);
+    });
+    println!("collisions: {}", u.collision_count());
+
+    let mut u = UidStore::with_capacity(3844);
+    for _ in 0..3800 {
+        u.next_with_prefix("id-", 2);
+    }
+    measure("next_with_prefix(2), nearly full", 40, || {
+        u.next_with_prefix("id-", 2);
+    });
+    println!("collisions: {}", u.collision_count());
+}
@@ -0,0 +1,52 @@
+//! Compares generating UID's on several threads at once using the per
+//! thread generator against the same work serialised behind one mutex,
+//! as every call was when a single global generator was shared. Run with
+//! `cargo bench --bench threads`.
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use uid_store::random_string;
+
+const PER_THREAD: usize = 200_000;
+
+static GLOBAL: Mutex<()> = Mutex::new(());
+
+// Generate `PER_THREAD` UID's on each of `threads` threads, returning
+// the time taken.
+fn run(threads: usize, locked: bool) -> Duration {
+    let start = Instant::now();
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            thread::spawn(move || {
+                for _ in 0..PER_THREAD {
+                    if locked {
+                        let _guard = GLOBAL.lock().unwrap();
+                        std::hint::black_box(random_string(12));
+                    } else {
+                        std::hint::black_box(random_string(12));
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    start.elapsed()
+}
+
+fn main() {
+    for threads in [1, 2, 4, 8] {
+        let total = (threads * PER_THREAD) as u32;
+        let free = run(threads, false);
+        let locked = run(threads, true);
+        println!(
+            "{} threads: thread local {:>8.1?}/uid, one mutex {:>8.1?}/uid",
+            threads,
+            free / total,
+            locked / total
+        );
+    }
+}
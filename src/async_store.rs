@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use crate::UidStore;
+
+/// AsyncUidStore is a handle to a `UidStore` for use in async code.
+/// It is like `SharedUidStore`, but waiting for the store yields to
+/// the async runtime rather than blocking the thread. Every clone draws
+/// from the same collection of UID values. Requires the `tokio` feature.
+#[derive(Debug, Clone, Default)]
+pub struct AsyncUidStore {
+    store: Arc<Mutex<UidStore>>,
+}
+
+impl AsyncUidStore {
+    pub fn new() -> AsyncUidStore {
+        AsyncUidStore::default()
+    }
+
+    /// Generate a UID string with a `length` number of characters.
+    pub async fn next(&self, length: usize) -> String {
+        self.store.lock().await.next(length).clone()
+    }
+
+    /// Generate a UID string that avoids commonly
+    /// confused letters such as i,I,1,L, 0,O,o.
+    pub async fn next_human(&self, length: usize) -> String {
+        self.store.lock().await.next_human(length).clone()
+    }
+
+    /// Register a UID with the shared `UidStore`. Returns `None` if
+    /// this string is unique and not previously seen. If the string
+    /// is already known and in use, a new uid string is returned.
+    pub async fn make_unique(&self, uid: &str) -> Option<String> {
+        self.store
+            .lock()
+            .await
+            .make_unique(uid)
+            .map(|id| id.to_string())
+    }
+
+    /// Returns true if a UID is already in use.
+    pub async fn contains(&self, id: &str) -> bool {
+        self.store.lock().await.contains(id)
+    }
+
+    /// Returns how many UID's have already been used.
+    pub async fn size(&self) -> usize {
+        self.store.lock().await.size()
+    }
+}
+
+impl From<UidStore> for AsyncUidStore {
+    fn from(store: UidStore) -> Self {
+        AsyncUidStore {
+            store: Arc::new(Mutex::new(store)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::AsyncUidStore;
+
+    #[tokio::test]
+    async fn test_async_tasks() {
+        let shared = AsyncUidStore::new();
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let s = shared.clone();
+                tokio::spawn(async move {
+                    let mut ids = Vec::new();
+                    for _ in 0..100 {
+                        ids.push(s.next(4).await);
+                        // Let the other tasks run between calls.
+                        tokio::task::yield_now().await;
+                    }
+                    ids
+                })
+            })
+            .collect();
+        let mut all = HashSet::new();
+        for handle in handles {
+            for id in handle.await.unwrap() {
+                assert!(all.insert(id), "duplicate uid");
+            }
+        }
+        assert_eq!(shared.size().await, 800);
+    }
+
+    #[tokio::test]
+    async fn test_lock_held_across_await() {
+        let shared = AsyncUidStore::new();
+        let guard = shared.store.lock().await;
+        let s = shared.clone();
+        let task = tokio::spawn(async move { s.next(8).await });
+        // The test runtime has a single thread, so this only returns if
+        // the task waits for the store without blocking the thread.
+        tokio::task::yield_now().await;
+        assert!(!task.is_finished());
+        drop(guard);
+        let id = task.await.unwrap();
+        assert!(shared.contains(&id).await);
+    }
+}
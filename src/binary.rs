@@ -0,0 +1,206 @@
+use std::fmt;
+
+use crate::UidStore;
+
+// The first bytes of data written by `UidStore::to_bytes()`.
+const MAGIC: &[u8; 4] = b"UIDS";
+
+// The version of the layout written by `UidStore::to_bytes()`.
+const VERSION: u8 = 1;
+
+/// Errors returned when `UidStore::from_bytes()` is given data that was
+/// not written by `UidStore::to_bytes()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The data does not start with the expected header.
+    InvalidHeader,
+    /// The data was written by an unsupported version of the format.
+    UnsupportedVersion(u8),
+    /// The data ends before the last UID it declares.
+    Truncated,
+    /// A UID, numbered from 0, is not valid UTF-8.
+    InvalidUtf8(u64),
+    /// Bytes remain after the last UID.
+    TrailingData,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidHeader => write!(f, "missing uid store header"),
+            DecodeError::UnsupportedVersion(v) => write!(f, "unsupported format version {}", v),
+            DecodeError::Truncated => write!(f, "data ends unexpectedly"),
+            DecodeError::InvalidUtf8(i) => write!(f, "uid {} is not valid utf-8", i),
+            DecodeError::TrailingData => write!(f, "unexpected data after the last uid"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl UidStore {
+    /// Write every UID held by this `UidStore` in a compact binary
+    /// layout, reversed by `from_bytes()`. The layout is:
+    ///
+    /// 1. The 4 bytes `UIDS`.
+    /// 2. A version byte, currently 1.
+    /// 3. The number of UID's as an 8 byte little endian integer.
+    /// 4. Each UID as its length in bytes, followed by its bytes. The
+    ///    length is an unsigned LEB128 integer, which is a single byte
+    ///    for UID's shorter than 128 bytes.
+    ///
+    /// UID's are written in the order given by `to_sorted_vec()`, so the
+    /// same UID's always produce the same bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut ids: Vec<&String> = self.items.iter().collect();
+        ids.sort_unstable();
+        let size: usize = ids.iter().map(|id| id.len() + 1).sum();
+        let mut data = Vec::with_capacity(MAGIC.len() + 9 + size);
+        data.extend_from_slice(MAGIC);
+        data.push(VERSION);
+        data.extend_from_slice(&(ids.len() as u64).to_le_bytes());
+        for id in ids {
+            write_length(&mut data, id.len());
+            data.extend_from_slice(id.as_bytes());
+        }
+        data
+    }
+
+    /// Create a `UidStore` holding the UID's in `data`, as written by
+    /// `to_bytes()`. Returns an error if `data` is not exactly in that
+    /// layout.
+    pub fn from_bytes(data: &[u8]) -> Result<UidStore, DecodeError> {
+        let data = data
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(DecodeError::InvalidHeader)?;
+        let (version, mut data) = data.split_first().ok_or(DecodeError::InvalidHeader)?;
+        if *version != VERSION {
+            return Err(DecodeError::UnsupportedVersion(*version));
+        }
+        if data.len() < 8 {
+            return Err(DecodeError::InvalidHeader);
+        }
+        let (count, rest) = data.split_at(8);
+        let count = u64::from_le_bytes(count.try_into().unwrap());
+        data = rest;
+
+        let mut store = UidStore::new();
+        // Each UID takes at least one byte, which bounds the space
+        // reserved for a corrupt count.
+        store.reserve(count.min(data.len() as u64) as usize);
+        for i in 0..count {
+            let length = read_length(&mut data)?;
+            if data.len() < length {
+                return Err(DecodeError::Truncated);
+            }
+            let (id, rest) = data.split_at(length);
+            let id = std::str::from_utf8(id).map_err(|_| DecodeError::InvalidUtf8(i))?;
+            store.items.insert(id.to_string());
+            data = rest;
+        }
+        if !data.is_empty() {
+            return Err(DecodeError::TrailingData);
+        }
+        Ok(store)
+    }
+}
+
+// Append `length` as an unsigned LEB128 integer, seven bits per byte
+// with the high bit set on every byte but the last.
+fn write_length(data: &mut Vec<u8>, mut length: usize) {
+    while length >= 0x80 {
+        data.push(length as u8 | 0x80);
+        length >>= 7;
+    }
+    data.push(length as u8);
+}
+
+// Read an unsigned LEB128 integer from the front of `data`.
+fn read_length(data: &mut &[u8]) -> Result<usize, DecodeError> {
+    let mut length: usize = 0;
+    let mut shift = 0;
+    loop {
+        let (byte, rest) = data.split_first().ok_or(DecodeError::Truncated)?;
+        *data = rest;
+        if shift >= usize::BITS {
+            return Err(DecodeError::Truncated);
+        }
+        length |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(length);
+        }
+        shift += 7;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DecodeError;
+    use crate::UidStore;
+
+    #[test]
+    fn test_round_trip() {
+        let mut u = UidStore::new();
+        u.next_many(1_000, 8);
+        u.make_unique("");
+        u.make_unique(&"x".repeat(300));
+        u.make_unique("é");
+        let data = u.to_bytes();
+        assert_eq!(UidStore::from_bytes(&data), Ok(u.clone()));
+        assert_eq!(data, UidStore::from_bytes(&data).expect("ok").to_bytes());
+        assert!(data.len() < 1_003 * 9 + 400);
+
+        let empty = UidStore::new().to_bytes();
+        assert_eq!(empty, b"UIDS\x01\0\0\0\0\0\0\0\0");
+        assert_eq!(UidStore::from_bytes(&empty).expect("ok").size(), 0);
+    }
+
+    #[test]
+    fn test_layout() {
+        let u: UidStore = ["b", "Ab"].map(String::from).into_iter().collect();
+        assert_eq!(u.to_bytes(), b"UIDS\x01\x02\0\0\0\0\0\0\0\x02Ab\x01b");
+
+        let mut long = UidStore::new();
+        long.make_unique(&"a".repeat(200));
+        assert_eq!(&long.to_bytes()[13..15], &[0xc8, 0x01]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let data = ["ab", "cd"]
+            .map(String::from)
+            .into_iter()
+            .collect::<UidStore>()
+            .to_bytes();
+        assert_eq!(UidStore::from_bytes(b""), Err(DecodeError::InvalidHeader));
+        assert_eq!(
+            UidStore::from_bytes(b"UIDX\x01"),
+            Err(DecodeError::InvalidHeader)
+        );
+        assert_eq!(
+            UidStore::from_bytes(&data[..8]),
+            Err(DecodeError::InvalidHeader)
+        );
+        assert_eq!(
+            UidStore::from_bytes(b"UIDS\x02"),
+            Err(DecodeError::UnsupportedVersion(2))
+        );
+        for end in 13..data.len() {
+            assert_eq!(
+                UidStore::from_bytes(&data[..end]),
+                Err(DecodeError::Truncated)
+            );
+        }
+        let mut extra = data.clone();
+        extra.push(0);
+        assert_eq!(UidStore::from_bytes(&extra), Err(DecodeError::TrailingData));
+        let mut invalid = data.clone();
+        invalid[17] = 0xff;
+        assert_eq!(
+            UidStore::from_bytes(&invalid),
+            Err(DecodeError::InvalidUtf8(1))
+        );
+        let huge = b"UIDS\x01\xff\xff\xff\xff\xff\xff\xff\xff";
+        assert_eq!(UidStore::from_bytes(huge), Err(DecodeError::Truncated));
+    }
+}
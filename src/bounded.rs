@@ -0,0 +1,25 @@
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An unsigned integer type whose values `UidStore::next_bounded()` can
+/// generate. This trait is sealed and implemented for `u8`, `u16`,
+/// `u32`, `u64` and `u128`.
+pub trait Bounded: sealed::Sealed + Into<u128> + Copy {
+    /// The largest value of this type.
+    const MAX: Self;
+}
+
+macro_rules! impl_bounded {
+    ($($t:ty),*) => {
+        $(
+            impl sealed::Sealed for $t {}
+
+            impl Bounded for $t {
+                const MAX: Self = <$t>::MAX;
+            }
+        )*
+    };
+}
+
+impl_bounded!(u8, u16, u32, u64, u128);
@@ -0,0 +1,108 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::number_to_uid_u128;
+
+/// CounterStore issues UID's from an increasing counter rather than
+/// at random, so they never collide and no record of previously
+/// issued values needs to be kept. Persist `counter()` and restore it
+/// with `starting_at()` to continue a sequence after a restart.
+///
+/// ```rust
+/// # use uid_store::*;
+/// let c = CounterStore::new();
+/// assert_eq!(c.next_sequential(), "A");
+/// assert_eq!(c.next_sequential(), "B");
+/// let restored = CounterStore::starting_at(c.counter());
+/// assert_eq!(restored.next_sequential(), "C");
+/// ```
+#[derive(Debug, Default)]
+pub struct CounterStore {
+    counter: AtomicU64,
+}
+
+impl CounterStore {
+    pub fn new() -> CounterStore {
+        CounterStore::default()
+    }
+
+    /// Create a `CounterStore` whose next value is `counter`.
+    pub fn starting_at(counter: u64) -> CounterStore {
+        CounterStore {
+            counter: AtomicU64::new(counter),
+        }
+    }
+
+    /// Returns the value that will be issued next.
+    pub fn counter(&self) -> u64 {
+        self.counter.load(Ordering::SeqCst)
+    }
+
+    /// Issue the next value of the counter. Combine with
+    /// `number_to_uid_sortable()` for UID's that sort in the
+    /// order they were issued.
+    ///
+    /// Panics if every `u64` value has been issued.
+    pub fn next_value(&self) -> u64 {
+        self.counter
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_add(1))
+            .expect("counter exhausted")
+    }
+
+    /// Issue the next value of the counter as a base62 string
+    /// using the same encoding as `number_to_uid()`.
+    pub fn next_sequential(&self) -> String {
+        number_to_uid_u128(self.next_value() as u128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::number_to_uid;
+    use crate::number_to_uid_sortable;
+    use crate::CounterStore;
+
+    #[test]
+    fn test_counter() {
+        let c = CounterStore::new();
+        for n in 0..1000 {
+            assert_eq!(c.next_sequential(), number_to_uid(n));
+        }
+        assert_eq!(c.counter(), 1000);
+        let restored = CounterStore::starting_at(c.counter());
+        assert_eq!(restored.next_value(), 1000);
+
+        let previous = number_to_uid_sortable(restored.next_value() as usize);
+        assert!(previous < number_to_uid_sortable(restored.next_value() as usize));
+
+        let last = CounterStore::starting_at(u64::MAX - 1);
+        assert_eq!(last.next_value(), u64::MAX - 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_counter_exhausted() {
+        CounterStore::starting_at(u64::MAX).next_value();
+    }
+
+    #[test]
+    fn test_counter_threads() {
+        let c = Arc::new(CounterStore::new());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let c = c.clone();
+                thread::spawn(move || (0..1000).map(|_| c.next_sequential()).collect::<Vec<_>>())
+            })
+            .collect();
+        let mut all = HashSet::new();
+        for handle in handles {
+            for id in handle.join().unwrap() {
+                assert!(all.insert(id));
+            }
+        }
+        assert_eq!(c.counter(), 4000);
+    }
+}
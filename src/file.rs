@@ -0,0 +1,201 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::is_valid_uid;
+use crate::UidStore;
+
+/// Errors returned when a file of UID's can not be loaded by
+/// `UidStore::load_validated_from_path()`.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// A line, numbered from 1, is not a valid base62 UID.
+    Invalid { line: usize, content: String },
+    /// A line, numbered from 1, repeats a UID from an earlier line.
+    Duplicate { line: usize, content: String },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "unable to read uid file: {}", e),
+            LoadError::Invalid { line, content } => {
+                write!(f, "line {} is not a valid uid: {:?}", line, content)
+            }
+            LoadError::Duplicate { line, content } => {
+                write!(f, "line {} repeats the uid {:?}", line, content)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> LoadError {
+        LoadError::Io(e)
+    }
+}
+
+impl UidStore {
+    /// Write every UID held by this `UidStore` to the file at `path`,
+    /// one UID per line, in the order given by `to_sorted_vec()`. Saving
+    /// the same UID's always produces the same file.
+    pub fn save_to_path<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        for id in self.to_sorted_vec() {
+            writeln!(file, "{}", id)?;
+        }
+        file.flush()
+    }
+
+    /// Create a `UidStore` holding the UID's in the file at `path`,
+    /// one UID per line, as written by `save_to_path()`. Blank lines
+    /// are ignored.
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> io::Result<UidStore> {
+        let mut store = UidStore::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if !line.is_empty() {
+                store.items.insert(line);
+            }
+        }
+        Ok(store)
+    }
+
+    /// Create a `UidStore` holding the UID's in the file at `path`, one
+    /// UID per line, rejecting the file if any line, including a blank
+    /// line, is not a valid base62 UID. Use this to detect a corrupt file
+    /// rather than importing whatever it contains. Repeated UID's are
+    /// loaded once, use `load_validated_unique_from_path()` to reject them.
+    pub fn load_validated_from_path<P: AsRef<Path>>(path: P) -> Result<UidStore, LoadError> {
+        load_validated(path.as_ref(), false)
+    }
+
+    /// Create a `UidStore` in the same way as `load_validated_from_path()`,
+    /// also rejecting the file if any UID appears on more than one line.
+    pub fn load_validated_unique_from_path<P: AsRef<Path>>(path: P) -> Result<UidStore, LoadError> {
+        load_validated(path.as_ref(), true)
+    }
+}
+
+fn load_validated(path: &Path, reject_duplicates: bool) -> Result<UidStore, LoadError> {
+    let mut store = UidStore::new();
+    for (index, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        if !is_valid_uid(&line) {
+            return Err(LoadError::Invalid {
+                line: index + 1,
+                content: line,
+            });
+        }
+        if store.items.contains(&line) {
+            if reject_duplicates {
+                return Err(LoadError::Duplicate {
+                    line: index + 1,
+                    content: line,
+                });
+            }
+            continue;
+        }
+        store.items.insert(line);
+    }
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::LoadError;
+    use crate::UidStore;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("uid_store_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_save_load() {
+        let path = temp_path("save_load");
+        let mut u = UidStore::new();
+        for _ in 0..100 {
+            u.next(8);
+        }
+        u.save_to_path(&path).expect("ok");
+        let saved = fs::read_to_string(&path).expect("ok");
+        assert_eq!(saved, u.to_sorted_vec().join("\n") + "\n");
+        let loaded = UidStore::load_from_path(&path).expect("ok");
+        assert_eq!(loaded, u);
+        fs::remove_file(&path).expect("ok");
+    }
+
+    #[test]
+    fn test_load_blank_lines() {
+        let path = temp_path("blank_lines");
+        fs::write(&path, "").expect("ok");
+        assert_eq!(UidStore::load_from_path(&path).expect("ok").size(), 0);
+        fs::write(&path, "abc\r\ndef\n\nabc\n\n\n").expect("ok");
+        let loaded = UidStore::load_from_path(&path).expect("ok");
+        assert_eq!(loaded.size(), 2);
+        assert!(loaded.contains("abc"));
+        assert!(loaded.contains("def"));
+        fs::remove_file(&path).expect("ok");
+        assert!(UidStore::load_from_path(&path).is_err());
+    }
+
+    #[test]
+    fn test_load_validated() {
+        let path = temp_path("validated");
+        let mut u = UidStore::new();
+        for _ in 0..100 {
+            u.next(8);
+        }
+        u.save_to_path(&path).expect("ok");
+        assert_eq!(UidStore::load_validated_from_path(&path).expect("ok"), u);
+        assert_eq!(
+            UidStore::load_validated_unique_from_path(&path).expect("ok"),
+            u
+        );
+
+        fs::write(&path, "abc\r\ndef\nabc\n").expect("ok");
+        assert_eq!(
+            UidStore::load_validated_from_path(&path)
+                .expect("ok")
+                .size(),
+            2
+        );
+        match UidStore::load_validated_unique_from_path(&path) {
+            Err(LoadError::Duplicate { line, content }) => {
+                assert_eq!(line, 3);
+                assert_eq!(content, "abc");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        fs::write(&path, "abc\nde f\n").expect("ok");
+        let e = UidStore::load_validated_from_path(&path).unwrap_err();
+        assert_eq!(e.to_string(), "line 2 is not a valid uid: \"de f\"");
+        fs::write(&path, "abc\n\ndef\n").expect("ok");
+        assert!(matches!(
+            UidStore::load_validated_from_path(&path),
+            Err(LoadError::Invalid { line: 2, .. })
+        ));
+
+        fs::remove_file(&path).expect("ok");
+        assert!(matches!(
+            UidStore::load_validated_from_path(&path),
+            Err(LoadError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+
+use crate::{random_string_into, CHARSET};
+
+/// A random base62 UID of exactly `N` characters stored inline, without
+/// a heap allocation. `FixedUid` is `Copy`, so it can be embedded in
+/// other types as cheaply as an integer.
+///
+/// ```rust
+/// # use uid_store::*;
+/// let uid = FixedUid::<8>::random();
+/// assert_eq!(uid.as_str().len(), 8);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FixedUid<const N: usize>([u8; N]);
+
+impl<const N: usize> FixedUid<N> {
+    /// Generate a random `FixedUid` using the same characters and
+    /// random number generator as `random_string()`.
+    pub fn random() -> FixedUid<N> {
+        let mut bytes = [0; N];
+        random_string_into(&mut bytes, CHARSET);
+        FixedUid(bytes)
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Every byte is an ASCII character from the charset.
+        std::str::from_utf8(&self.0).unwrap()
+    }
+
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> fmt::Display for FixedUid<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedUid<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FixedUid").field(&self.as_str()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::FixedUid;
+    use crate::CHARSET;
+
+    #[test]
+    fn test_random() {
+        let mut all = HashSet::new();
+        for _ in 0..10_000 {
+            let uid = FixedUid::<8>::random();
+            assert!(uid.as_bytes().iter().all(|c| CHARSET.contains(c)));
+            assert_eq!(uid.as_str().len(), 8);
+            assert_eq!(uid.to_string(), uid.as_str());
+            assert!(all.insert(uid));
+        }
+        let uid = FixedUid::<4>::random();
+        let copy = uid;
+        assert_eq!(uid, copy);
+        assert_eq!(
+            format!("{:?}", uid),
+            format!("FixedUid({:?})", uid.as_str())
+        );
+        assert_eq!(FixedUid::<0>::random().as_str(), "");
+    }
+}
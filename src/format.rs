@@ -0,0 +1,85 @@
+use crate::random::Source;
+use crate::{generate_into, CHARSET, READABLE_CHARSET};
+
+/// UidFormat describes the shape of a UID so the same policy can be
+/// reused wherever a UID is generated, instead of passing the length
+/// and other options to every call.
+///
+/// ```rust
+/// # use uid_store::*;
+/// let format = UidFormat::new().length(6).prefix("ord_").human(true);
+/// let mut u = UidStore::new();
+/// let uid = u.next_formatted(&format);
+/// assert!(uid.starts_with("ord_"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UidFormat {
+    pub(crate) length: usize,
+    pub(crate) charset: Vec<u8>,
+    pub(crate) prefix: String,
+    pub(crate) suffix: String,
+}
+
+impl UidFormat {
+    /// A format for 8 character base62 UID's with no prefix or suffix.
+    pub fn new() -> UidFormat {
+        UidFormat {
+            length: 8,
+            charset: CHARSET.to_vec(),
+            prefix: String::new(),
+            suffix: String::new(),
+        }
+    }
+
+    /// Set the number of random characters, not counting
+    /// any prefix or suffix.
+    pub fn length(mut self, length: usize) -> UidFormat {
+        self.length = length;
+        self
+    }
+
+    /// Draw random characters from `charset`, where each byte is one
+    /// character. `UidStore::try_next_formatted()` returns an error if
+    /// `charset` is empty or contains a byte that is not ASCII.
+    pub fn charset(mut self, charset: &[u8]) -> UidFormat {
+        self.charset = charset.to_vec();
+        self
+    }
+
+    /// Draw random characters from the charset used by
+    /// `human_random_string()` if `human` is true. If `human` is false
+    /// and that charset is in use, return to the base62 charset; a
+    /// charset set by `charset()` is kept.
+    pub fn human(mut self, human: bool) -> UidFormat {
+        if human {
+            self.charset = READABLE_CHARSET.to_vec();
+        } else if self.charset == READABLE_CHARSET {
+            self.charset = CHARSET.to_vec();
+        }
+        self
+    }
+
+    /// Add `prefix` to the start of every UID.
+    pub fn prefix(mut self, prefix: &str) -> UidFormat {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    /// Add `suffix` to the end of every UID.
+    pub fn suffix(mut self, suffix: &str) -> UidFormat {
+        self.suffix = suffix.to_string();
+        self
+    }
+
+    pub(crate) fn generate_into(&self, rng: &mut Source, buf: &mut String) {
+        buf.push_str(&self.prefix);
+        generate_into(self.length, &self.charset, rng, buf);
+        buf.push_str(&self.suffix);
+    }
+}
+
+impl Default for UidFormat {
+    fn default() -> Self {
+        UidFormat::new()
+    }
+}
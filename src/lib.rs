@@ -19,6 +19,16 @@
 //! let uid = human_random_string(8);
 //! ```
 //!
+//! These functions use a fast pseudo random number generator that
+//! is not cryptographically secure. When the `secure` feature is
+//! enabled, `random_string_secure`, `random_number_secure` and
+//! `human_random_string_secure` draw from the operating system
+//! random number generator instead and are suitable for session
+//! tokens and other secrets.
+//!
+//! When the `tokio` feature is enabled, `AsyncUidStore` shares a
+//! `UidStore` between async tasks without blocking the runtime.
+//!
 //! Convert a number to and from a base62 uid:
 //!
 //! ```rust
@@ -64,80 +74,869 @@
 //!
 
 use std::collections::HashSet;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
+use random::{PseudoRandom, Source};
+
+#[cfg(feature = "tokio")]
+mod async_store;
+mod binary;
+mod bounded;
+mod counter;
+mod file;
+mod fixed;
+mod format;
+mod pool;
 mod random;
+mod shared;
+mod uid;
+
+#[cfg(feature = "tokio")]
+pub use async_store::AsyncUidStore;
+pub use binary::DecodeError;
+pub use bounded::Bounded;
+pub use counter::CounterStore;
+pub use file::LoadError;
+pub use fixed::FixedUid;
+pub use format::UidFormat;
+pub use pool::UidPool;
+pub use random::Rng;
+pub use shared::SharedUidStore;
+pub use uid::Uid;
+
+/// Errors returned when a UID can not be generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UidError {
+    /// No unused UID could be found, the keyspace is full
+    /// or very close to full.
+    Exhausted,
+    /// A charset with no characters was supplied.
+    EmptyCharset,
+    /// A value does not fit in the space available for it.
+    Overflow,
+    /// Every UID generated was rejected, for example by a blocklist
+    /// that matches too much.
+    Rejected,
+    /// More UID's collided with ones already in use than the limit set
+    /// by `UidStore::set_max_retries()`.
+    RetryLimit,
+    /// The weights given for a charset do not have one entry for each
+    /// character, or are all zero.
+    InvalidWeights,
+    /// A charset with too few characters for the requested UID was
+    /// supplied.
+    CharsetTooSmall,
+    /// A charset containing a byte that is not an ASCII character was
+    /// supplied.
+    NonAsciiCharset,
+}
+
+impl fmt::Display for UidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UidError::Exhausted => write!(f, "no unused uid available"),
+            UidError::EmptyCharset => write!(f, "charset is empty"),
+            UidError::Overflow => write!(f, "value does not fit"),
+            UidError::Rejected => write!(f, "every generated uid was rejected"),
+            UidError::RetryLimit => write!(f, "retry limit reached, every uid tried was in use"),
+            UidError::InvalidWeights => write!(f, "weights do not match the charset"),
+            UidError::CharsetTooSmall => write!(f, "charset has too few characters"),
+            UidError::NonAsciiCharset => write!(f, "charset is not ascii"),
+        }
+    }
+}
+
+impl std::error::Error for UidError {}
+
+/// The error returned by `UidStore::insert_strict()` when the
+/// UID is already in use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadyExists;
+
+impl fmt::Display for AlreadyExists {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "uid is already in use")
+    }
+}
+
+impl std::error::Error for AlreadyExists {}
 
 /// UidStore holds a collection of previously generated UID
 /// values to ensure a value is only ever generated once.
-#[derive(Debug)]
+///
+/// Cloning a `UidStore` copies the UID values it holds, and the
+/// state of its random number generator if it was created with a
+/// seed. Two stores are equal when they hold the same UID values.
+#[derive(Debug, Clone)]
 pub struct UidStore {
     items: HashSet<String>,
+    rng: Source,
+    generated: u64,
+    collisions: usize,
+    min_regen_length: usize,
+    population_length: Option<usize>,
+    reserved_prefixes: HashSet<String>,
+    sequence_u16: Option<(u16, u32)>,
+    // Every number below this is known to be in use, so
+    // next_free_u16_sequential() starts from here. Reset to zero
+    // whenever a UID is released.
+    free_u16: u32,
+    max_retries: usize,
+    on_collision: Option<CollisionHook>,
+    // Candidates are generated into this buffer, so a collision does not
+    // allocate and the buffer keeps its capacity from one call to the next.
+    scratch: String,
+}
+
+// A callback set by `UidStore::on_collision()`, shared between clones of
+// a store in the same way as a generator given to `with_rng()`.
+#[derive(Clone)]
+struct CollisionHook(Arc<Mutex<CollisionFn>>);
+
+type CollisionFn = Box<dyn FnMut(&str) + Send>;
+
+impl fmt::Debug for CollisionHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CollisionHook")
+    }
 }
 
 impl UidStore {
     pub fn new() -> UidStore {
+        UidStore::with_source(Source::Global)
+    }
+
+    fn with_source(rng: Source) -> UidStore {
+        UidStore {
+            items: HashSet::new(),
+            rng,
+            generated: 0,
+            collisions: 0,
+            min_regen_length: 0,
+            population_length: None,
+            reserved_prefixes: HashSet::new(),
+            sequence_u16: None,
+            free_u16: 0,
+            max_retries: usize::MAX,
+            on_collision: None,
+            scratch: String::new(),
+        }
+    }
+
+    /// Create a `UidStore` with space for at least `capacity` UID's
+    /// before it needs to reallocate.
+    pub fn with_capacity(capacity: usize) -> UidStore {
+        let mut store = UidStore::new();
+        store.items.reserve(capacity);
+        store
+    }
+
+    /// Create a `UidStore` that owns a random number generator
+    /// initialised with `seed`. Two stores created with the same
+    /// seed generate the same sequence of UID values, which is
+    /// useful for writing deterministic tests.
+    ///
+    /// The generator can not run from a seed of all zeros, so
+    /// `[0, 0, 0, 0]` is silently replaced with a seed taken from the
+    /// current time, and a store created with it is not reproducible.
+    pub fn with_seed(seed: [u64; 4]) -> UidStore {
+        UidStore::with_source(Source::Seeded(PseudoRandom::new_with_seed(seed)))
+    }
+
+    /// Create a `UidStore` with its own random number generator seeded
+    /// from the text of `seed`, such as the name of a test. Two stores
+    /// created with the same string generate the same sequence of UID
+    /// values.
+    ///
+    /// ```rust
+    /// # use uid_store::*;
+    /// let mut a = UidStore::with_string_seed("fixtures");
+    /// let mut b = UidStore::with_string_seed("fixtures");
+    /// assert_eq!(a.next(8), b.next(8));
+    /// ```
+    pub fn with_string_seed(seed: &str) -> UidStore {
+        UidStore::with_source(Source::Seeded(PseudoRandom::new_with_string_seed(seed)))
+    }
+
+    /// Create a `UidStore` that draws random numbers from `rng`. Cloning
+    /// the store shares `rng` between the original and the clone.
+    pub fn with_rng<R: Rng + Send + 'static>(rng: R) -> UidStore {
+        UidStore::with_source(Source::Custom(Arc::new(Mutex::new(rng))))
+    }
+
+    /// Create a `UidStore` holding `items`, also returning every item
+    /// that repeats an earlier one, in the order they were found. An
+    /// item that appears three times is reported twice. Collecting into
+    /// a `UidStore` ignores repeats, use this to audit imported UID's.
+    ///
+    /// ```rust
+    /// # use uid_store::*;
+    /// let items = ["a", "b", "a"].map(String::from);
+    /// let (u, repeated) = UidStore::from_strings_checked(items);
+    /// assert_eq!(u.size(), 2);
+    /// assert_eq!(repeated, ["a"]);
+    /// ```
+    pub fn from_strings_checked(
+        items: impl IntoIterator<Item = String>,
+    ) -> (UidStore, Vec<String>) {
+        let mut store = UidStore::new();
+        let mut repeated = Vec::new();
+        for id in items {
+            if store.items.contains(&id) {
+                repeated.push(id);
+            } else {
+                store.items.insert(id);
+            }
+        }
+        (store, repeated)
+    }
+
+    /// Replace the state of this store's random number generator with
+    /// `seed`, for example after mixing in entropy from elsewhere. UID's
+    /// already in use are kept, only the UID's generated from now on
+    /// change, following the same sequence as a store created by
+    /// `with_seed()` with the same seed. As with `with_seed()`, a seed
+    /// of all zeros is replaced with one taken from the current time.
+    ///
+    /// This is meant for stores created by `with_seed()` or
+    /// `with_string_seed()`. Any other store stops drawing from the
+    /// shared per thread generator, or the one given to `with_rng()`,
+    /// and uses its own generator from then on.
+    pub fn reseed(&mut self, seed: [u64; 4]) {
+        self.rng = Source::Seeded(PseudoRandom::new_with_seed(seed));
+    }
+
+    /// Create an empty `UidStore` with the same settings as this one and
+    /// a random number generator whose sequence does not overlap with
+    /// the sequence of this store, for use by an independent worker.
+    ///
+    /// The new store continues the current sequence, and the generator
+    /// of this store jumps 2^128 steps ahead to start a new one, so
+    /// every fork of a store is distinct. Fork every worker from the same
+    /// store, as a fork of a fork continues into the sequence of the
+    /// next fork of the original store. A store created with
+    /// `with_rng()` shares its generator with its forks instead.
+    pub fn fork(&mut self) -> UidStore {
         UidStore {
             items: HashSet::new(),
+            rng: self.rng.fork(),
+            generated: 0,
+            collisions: 0,
+            min_regen_length: self.min_regen_length,
+            population_length: self.population_length,
+            reserved_prefixes: self.reserved_prefixes.clone(),
+            sequence_u16: None,
+            free_u16: 0,
+            max_retries: self.max_retries,
+            on_collision: self.on_collision.clone(),
+            scratch: String::new(),
         }
     }
 
     /// Generate a UID string with a `length` number of characters.
+    /// Panics if every UID of this length is already in use,
+    /// use `try_next()` to handle this case.
     pub fn next(&mut self, length: usize) -> &String {
-        loop {
-            let id = random_string(length);
-            if !self.items.insert(id.clone()) {
+        expect_unique(self.try_next(length))
+    }
+
+    /// Generate a UID with a `length` number of characters in the same
+    /// way as `next()`, returned as a `Uid`. Panics if `length` is zero
+    /// or in the same way as `next()`.
+    pub fn next_uid(&mut self, length: usize) -> Uid {
+        assert!(length > 0, "length must not be zero");
+        Uid::new_unchecked(self.next(length).clone())
+    }
+
+    /// Generate an upper case UID string with a `length` number of
+    /// characters. See `random_string_upper()`. Panics in the same way
+    /// as `next()`.
+    pub fn next_upper(&mut self, length: usize) -> &String {
+        let attempts = max_attempts(length, UPPERCASE_CHARSET.len());
+        let result = self.insert_unique(attempts, |rng, buf| {
+            generate_into(length, CHARSET, rng, buf);
+            buf.make_ascii_uppercase();
+        });
+        expect_unique(result)
+    }
+
+    /// Generate a UID string with a `length` number of characters where
+    /// no character appears twice in a row. See
+    /// `random_string_no_repeats()`. Panics in the same way as `next()`.
+    pub fn next_no_repeats(&mut self, length: usize) -> &String {
+        let attempts = max_attempts(length, CHARSET.len() - 1);
+        let result = self.insert_unique(attempts, |rng, buf| {
+            generate_no_repeats_into(length, CHARSET, rng, buf)
+        });
+        expect_unique(result)
+    }
+
+    /// Generate a UID string with a `length` number of characters,
+    /// returned with the number of collisions with UID's already in use
+    /// before an unused one was found. A count above zero suggests the
+    /// keyspace of this length is filling up. Panics in the same way
+    /// as `next()`.
+    pub fn next_with_attempts(&mut self, length: usize) -> (String, usize) {
+        let before = self.collisions;
+        let id = self.next(length).clone();
+        (id, self.collisions - before)
+    }
+
+    /// Generate a UID string with a `length` number of characters.
+    /// Returns `UidError::Exhausted` if no unused UID could be found,
+    /// which happens when most UID's of this length are in use, and
+    /// `UidError::Rejected` if too many start with a reserved prefix.
+    pub fn try_next(&mut self, length: usize) -> Result<&String, UidError> {
+        let attempts = max_attempts(length, CHARSET.len());
+        self.insert_unique_where(
+            attempts,
+            |rng, buf| generate_into(length, CHARSET, rng, buf),
+            |store, id| !store.is_reserved(id),
+        )
+    }
+
+    /// Generate a UID string with a `length` number of characters in the
+    /// same way as `next()`, writing it to `buf` in place of its previous
+    /// contents. `buf` keeps its allocation from one call to the next, so
+    /// generating a UID only allocates the copy held by this store.
+    /// Panics in the same way as `next()`.
+    ///
+    /// ```rust
+    /// # use uid_store::*;
+    /// let mut u = UidStore::new();
+    /// let mut buf = String::new();
+    /// for _ in 0..10 {
+    ///     u.next_reuse(8, &mut buf);
+    ///     assert!(u.contains(&buf));
+    /// }
+    /// ```
+    pub fn next_reuse(&mut self, length: usize, buf: &mut String) {
+        let result = self.find_unused(
+            buf,
+            max_attempts(length, CHARSET.len()),
+            |rng, buf| generate_into(length, CHARSET, rng, buf),
+            |store, id| !store.is_reserved(id),
+        );
+        expect_unique(result);
+        self.generated += 1;
+        self.items.insert(buf.clone());
+    }
+
+    /// Generate a UID string with a `length` number of characters that,
+    /// once lower cased, does not contain any string in `blocklist`.
+    /// Use this to keep unwanted words out of UID's shown to people.
+    /// Panics if no acceptable UID could be found, use
+    /// `try_next_clean()` to handle this case.
+    pub fn next_clean(&mut self, length: usize, blocklist: &HashSet<String>) -> &String {
+        expect_unique(self.try_next_clean(length, blocklist))
+    }
+
+    /// Generate a UID string with a `length` number of characters that,
+    /// once lower cased, does not contain any string in `blocklist`.
+    /// Returns `UidError::Rejected` if the blocklist rejects too many
+    /// UID's in a row, and `UidError::Exhausted` if no unused UID could
+    /// be found.
+    pub fn try_next_clean(
+        &mut self,
+        length: usize,
+        blocklist: &HashSet<String>,
+    ) -> Result<&String, UidError> {
+        let blocklist: Vec<String> = blocklist.iter().map(|b| b.to_lowercase()).collect();
+        let attempts = max_attempts(length, CHARSET.len());
+        self.insert_unique_where(
+            attempts,
+            |rng, buf| generate_into(length, CHARSET, rng, buf),
+            |_, id| {
+                let id = id.to_lowercase();
+                !blocklist.iter().any(|b| id.contains(b.as_str()))
+            },
+        )
+    }
+
+    /// Generate a UID string with a `length` number of characters that
+    /// is neither in use nor in `exclude`, such as UID's a person has
+    /// recently held. The excluded UID's are not added to this store.
+    /// Panics if no acceptable UID could be found, for example because
+    /// `exclude` holds most UID's of this length.
+    pub fn next_excluding(&mut self, length: usize, exclude: &HashSet<String>) -> &String {
+        let attempts = max_attempts(length, CHARSET.len());
+        let result = self.insert_unique_where(
+            attempts,
+            |rng, buf| generate_into(length, CHARSET, rng, buf),
+            |store, id| !exclude.contains(id) && !store.is_reserved(id),
+        );
+        expect_unique(result)
+    }
+
+    /// Generate a UID string with a random length between `min` and `max`
+    /// characters inclusive, so the length of a UID reveals nothing about
+    /// when it was issued. Panics if `min` is zero, `min` is greater than
+    /// `max`, or every UID in the range is already in use.
+    pub fn next_variable(&mut self, min: usize, max: usize) -> &String {
+        assert!(min > 0, "min must not be zero");
+        assert!(min <= max, "min must not be greater than max");
+        let attempts = max_attempts(max, CHARSET.len());
+        let result = self.insert_unique(attempts, |rng, buf| {
+            let length = min + random_up_to(max - min, rng);
+            generate_into(length, CHARSET, rng, buf)
+        });
+        expect_unique(result)
+    }
+
+    /// Generate a UID string made of `prefix` followed by a random
+    /// `length` number of characters. The full string, including the
+    /// prefix, is stored to ensure it is only generated once.
+    pub fn next_with_prefix(&mut self, prefix: &str, length: usize) -> &String {
+        let attempts = max_attempts(length, CHARSET.len());
+        let result = self.insert_unique(attempts, |rng, buf| {
+            buf.push_str(prefix);
+            generate_into(length, CHARSET, rng, buf);
+        });
+        expect_unique(result)
+    }
+
+    /// Generate a UID string made of a random `length` number of
+    /// characters followed by `suffix`. The full string, including
+    /// the suffix, is stored to ensure it is only generated once.
+    pub fn next_with_suffix(&mut self, suffix: &str, length: usize) -> &String {
+        let attempts = max_attempts(length, CHARSET.len());
+        let result = self.insert_unique(attempts, |rng, buf| {
+            generate_into(length, CHARSET, rng, buf);
+            buf.push_str(suffix);
+        });
+        expect_unique(result)
+    }
+
+    /// Generate a UID string with a `length` number of characters that
+    /// is not currently in use, without marking it as used. Call
+    /// `commit()` to register the UID once it has been accepted.
+    ///
+    /// Between `peek()` and `commit()` another call to `peek()` or
+    /// `next()` may produce the same UID. When `commit()` returns
+    /// false the UID has been taken and a new one should be peeked.
+    /// Like `next()`, this never returns a UID that starts with a
+    /// prefix reserved by `reserve_prefix()`.
+    pub fn peek(&mut self, length: usize) -> String {
+        let mut id = String::with_capacity(length);
+        let result = self.find_unused(
+            &mut id,
+            max_attempts(length, CHARSET.len()),
+            |rng, buf| generate_into(length, CHARSET, rng, buf),
+            |store, id| !store.is_reserved(id),
+        );
+        expect_unique(result);
+        id
+    }
+
+    /// Register a UID previously returned by `peek()`. Returns false
+    /// if the UID is already in use.
+    pub fn commit(&mut self, uid: &str) -> bool {
+        if !self.try_claim(uid) {
+            return false;
+        }
+        self.generated += 1;
+        true
+    }
+
+    /// Generate a UID string in the shape described by `format`.
+    /// The full string, including any prefix or suffix, is stored
+    /// to ensure it is only generated once. Panics in the same way
+    /// as `next()`, use `try_next_formatted()` to handle this case.
+    pub fn next_formatted(&mut self, format: &UidFormat) -> &String {
+        expect_unique(self.try_next_formatted(format))
+    }
+
+    /// Generate a UID string in the shape described by `format`.
+    /// Returns an error if the format has an empty or non-ASCII
+    /// charset, or no unused UID could be found.
+    pub fn try_next_formatted(&mut self, format: &UidFormat) -> Result<&String, UidError> {
+        check_charset(&format.charset)?;
+        let attempts = max_attempts(format.length, format.charset.len());
+        self.insert_unique(attempts, |rng, buf| format.generate_into(rng, buf))
+    }
+
+    /// Generate a UID string split into groups by a separator. See
+    /// `grouped_string()`. The grouped string is stored to ensure it
+    /// is only generated once. Panics in the same way as `next()`.
+    pub fn next_grouped(
+        &mut self,
+        total_len: usize,
+        group_size: usize,
+        separator: char,
+    ) -> &String {
+        let attempts = max_attempts(total_len, CHARSET.len());
+        let result = self.insert_unique(attempts, |rng, buf| {
+            generate_grouped_into(total_len, group_size, separator, rng, buf)
+        });
+        expect_unique(result)
+    }
+
+    /// Generate a UID string that sorts in the order it was created.
+    /// See `ulid_string()`. If the UID is already in use, the random
+    /// part is regenerated keeping the same time.
+    pub fn next_ulid(&mut self) -> &String {
+        let time = encode_sortable(now_millis(), ULID_TIME_WIDTH);
+        let attempts = max_attempts(ULID_RANDOM_LENGTH, CHARSET.len());
+        let result = self.insert_unique(attempts, |rng, buf| {
+            buf.push_str(&time);
+            generate_into(ULID_RANDOM_LENGTH, CHARSET, rng, buf);
+        });
+        expect_unique(result)
+    }
+
+    /// Generate a random version 4 UUID string that is not already in
+    /// use. See `uuid_v4_string()`.
+    pub fn next_uuid(&mut self) -> &String {
+        // A version 4 UUID holds 122 random bits.
+        let result = self.insert_unique(max_number_attempts(u128::MAX >> 6), generate_uuid_v4_into);
+        expect_unique(result)
+    }
+
+    /// Generate a UID string that alternates between consonants and
+    /// vowels. See `pronounceable_string()`. Panics in the same way
+    /// as `next()`.
+    pub fn next_pronounceable(&mut self, length: usize) -> &String {
+        let consonants = keyspace(length.div_ceil(2), CONSONANTS.len());
+        let vowels = keyspace(length / 2, VOWELS.len());
+        let attempts = consonants
+            .saturating_mul(vowels)
+            .saturating_mul(RETRY_FACTOR);
+        let result = self.insert_unique(attempts, |rng, buf| {
+            generate_pronounceable_into(length, rng, buf)
+        });
+        expect_unique(result)
+    }
+
+    /// Generate a UID string following `template`. See
+    /// `templated_string()` for the template syntax. Panics in the
+    /// same way as `next()`.
+    pub fn next_templated(&mut self, template: &str) -> &String {
+        let parts = parse_template(template);
+        let attempts = parts
+            .iter()
+            .fold(1usize, |keyspace, part| match part {
+                TemplatePart::Literal(_) => keyspace,
+                TemplatePart::Random(charset) => keyspace.saturating_mul(charset.len()),
+            })
+            .saturating_mul(RETRY_FACTOR);
+        let result = self.insert_unique(attempts, |rng, buf| {
+            generate_templated_into(&parts, rng, buf)
+        });
+        expect_unique(result)
+    }
+
+    /// Generate `count` UID strings each with a `length` number
+    /// of characters. Panics in the same way as `next()`.
+    pub fn next_many(&mut self, count: usize, length: usize) -> Vec<String> {
+        self.items.reserve(count);
+        (0..count).map(|_| self.next(length).clone()).collect()
+    }
+
+    /// Generate a UID string with a length chosen by `recommended_length()`
+    /// to keep the chance of a collision between `expected_total` UID's
+    /// below 1%. The length is chosen on the first call and reused by
+    /// every later call, so all UID's from this method share a length
+    /// even if a different `expected_total` is given. Panics in the same
+    /// way as `next()`.
+    pub fn next_for_population(&mut self, expected_total: usize) -> &String {
+        let length = *self
+            .population_length
+            .get_or_insert_with(|| recommended_length(expected_total, CHARSET.len()));
+        self.next(length)
+    }
+
+    /// Returns true if generating `additional` more UID strings with a
+    /// `length` number of characters, on top of those already in the
+    /// store, risks a collision chance above 1%, so a longer length
+    /// should be used. This applies the same birthday bound as
+    /// `recommended_length()` to `size() + additional` UID's.
+    ///
+    /// ```rust
+    /// # use uid_store::*;
+    /// let u = UidStore::new();
+    /// assert!(u.would_likely_collide(1_000, 4));
+    /// assert!(!u.would_likely_collide(1_000, 5));
+    /// ```
+    pub fn would_likely_collide(&self, additional: usize, length: usize) -> bool {
+        let n = self.items.len().saturating_add(additional) as f64;
+        let keyspace = (CHARSET.len() as f64).powi(length.min(i32::MAX as usize) as i32);
+        n * (n - 1.0) / (2.0 * keyspace) > COLLISION_PROBABILITY
+    }
+
+    /// Returns true if at least 90% as many UID's as there are base62
+    /// strings of `length` characters are in use, at which point finding
+    /// an unused one takes many attempts and a longer length should be
+    /// used. `size()` counts UID's of every length, so this can also
+    /// report true when few UID's of `length` characters are in use.
+    ///
+    /// ```rust
+    /// # use uid_store::*;
+    /// let mut u = UidStore::new();
+    /// u.next_many(56, 1);
+    /// assert!(u.is_saturated(1));
+    /// assert!(!u.is_saturated(2));
+    /// ```
+    pub fn is_saturated(&self, length: usize) -> bool {
+        let keyspace = keyspace(length, CHARSET.len()) as f64;
+        self.items.len() as f64 >= keyspace * SATURATION
+    }
+
+    /// Returns an iterator that generates a new UID string with a
+    /// `length` number of characters each time it is advanced. The
+    /// iterator ends if every UID of this length is already in use.
+    pub fn iter_unique(&mut self, length: usize) -> impl Iterator<Item = String> + '_ {
+        std::iter::from_fn(move || self.try_next(length).ok().cloned())
+    }
+
+    /// Insert the first value written by `generate` that is not already
+    /// in use, giving up after `max_attempts` collisions.
+    fn insert_unique<F>(&mut self, max_attempts: usize, generate: F) -> Result<&String, UidError>
+    where
+        F: FnMut(&mut Source, &mut String),
+    {
+        self.insert_unique_where(max_attempts, generate, |_, _| true)
+    }
+
+    /// Insert the first value written to an empty buffer by `generate`
+    /// that is not already in use and is accepted by `accept`, giving up
+    /// after `max_attempts` attempts, `MAX_REJECTIONS` rejections in a
+    /// row, or more collisions than `max_retries`.
+    ///
+    /// The buffer is this store's `scratch`, so a collision allocates
+    /// nothing unless `generate` itself does, as the numeric generators
+    /// do when encoding a number. An unused value is copied into the set
+    /// with one allocation.
+    fn insert_unique_where<F, A>(
+        &mut self,
+        max_attempts: usize,
+        generate: F,
+        accept: A,
+    ) -> Result<&String, UidError>
+    where
+        F: FnMut(&mut Source, &mut String),
+        A: FnMut(&UidStore, &str) -> bool,
+    {
+        let mut id = std::mem::take(&mut self.scratch);
+        let result = self.find_unused(&mut id, max_attempts, generate, accept);
+        self.scratch = id;
+        result?;
+        self.generated += 1;
+        // There is no stable way to insert into a `HashSet` and borrow
+        // the stored value back, so the copy is looked up through
+        // `scratch`, which still holds the same value.
+        self.items.insert(self.scratch.clone());
+        Ok(self.items.get(&self.scratch).unwrap())
+    }
+
+    /// Insert a copy of `id`, which the caller has checked is not already
+    /// in use, and return the copy held by this store.
+    fn insert_new(&mut self, id: &str) -> &String {
+        // There is no stable way to insert into a `HashSet` and borrow
+        // the stored value back, so it is looked up a second time.
+        self.items.insert(id.to_string());
+        self.items.get(id).unwrap()
+    }
+
+    /// Write the first value generated by `generate` that is not already
+    /// in use and is accepted by `accept` to `buf`, without marking it as
+    /// used. `buf` is cleared before each attempt. Gives up after
+    /// `max_attempts` attempts, `MAX_REJECTIONS` rejections in a row, or
+    /// more collisions than `max_retries`.
+    fn find_unused<F, A>(
+        &mut self,
+        buf: &mut String,
+        max_attempts: usize,
+        mut generate: F,
+        mut accept: A,
+    ) -> Result<(), UidError>
+    where
+        F: FnMut(&mut Source, &mut String),
+        A: FnMut(&UidStore, &str) -> bool,
+    {
+        let mut rejections = 0;
+        let mut retries = 0;
+        for _ in 0..max_attempts {
+            buf.clear();
+            generate(&mut self.rng, buf);
+            if !accept(self, buf) {
+                rejections += 1;
+                if rejections >= MAX_REJECTIONS {
+                    return Err(UidError::Rejected);
+                }
                 continue;
             }
-            return self.items.get(&id).unwrap();
+            rejections = 0;
+            if !self.items.contains(buf.as_str()) {
+                return Ok(());
+            }
+            self.collided(buf);
+            retries += 1;
+            if retries > self.max_retries {
+                return Err(UidError::RetryLimit);
+            }
         }
+        Err(UidError::Exhausted)
     }
 
     /// Generate a UID string that avoids commonly
-    /// confused letters such as i,I,1,L, 0,O,o.
+    /// confused letters such as i,I,1,L, 0,O,o. Panics in the same way
+    /// as `next()` once no unused UID of this length can be found.
     pub fn next_human(&mut self, length: usize) -> &String {
-        loop {
-            let id = human_random_string(length);
-            if !self.items.insert(id.clone()) {
-                continue;
-            }
-            return self.items.get(&id).unwrap();
-        }
+        let result = self.insert_unique_where(
+            max_attempts(length, READABLE_CHARSET.len()),
+            |rng, buf| generate_into(length, READABLE_CHARSET, rng, buf),
+            |store, id| !store.is_reserved(id),
+        );
+        expect_unique(result)
     }
 
-    /// Generate a UID string that represents a random `u16` number.
-    /// The length of the string depends on the size of the number.
+    /// Generate a UID string that represents a random `u16` number,
+    /// any of the 65536 from 0 to `u16::MAX` inclusive. The length of
+    /// the string depends on the size of the number.
     pub fn next_u16(&mut self) -> &String {
-        loop {
-            let id = random_max_size(u16::MAX as usize);
-            if !self.items.insert(id.clone()) {
-                continue;
-            }
-            return self.items.get(&id).unwrap();
-        }
+        self.next_bounded::<u16>()
     }
 
-    /// Generate a UID string that represents a random `u32` number.
-    /// The length of the string depends on the size of the number.
+    /// Generate a UID string that represents a random `u32` number,
+    /// from 0 to `u32::MAX` inclusive. The length of the string depends
+    /// on the size of the number.
     pub fn next_u32(&mut self) -> &String {
-        loop {
-            let id = random_max_size(u32::MAX as usize);
-            if !self.items.insert(id.clone()) {
-                continue;
+        self.next_bounded::<u32>()
+    }
+
+    /// Generate a UID string that represents a random `u64` number,
+    /// from 0 to `u64::MAX` inclusive. The length of the string depends
+    /// on the size of the number.
+    pub fn next_u64(&mut self) -> &String {
+        self.next_bounded::<u64>()
+    }
+
+    /// Generate a UID string that represents a `u16` number, visiting
+    /// every `u16` number exactly once in an order that does not look
+    /// sequential. Unlike `next_u16()` this never slows down as the
+    /// keyspace fills. Numbers already in use, for example from
+    /// `next_u16()`, are skipped. Panics once all 65536 have been visited.
+    ///
+    /// The numbers follow the linear congruential generator
+    /// `x = (25173 * x + 13849) mod 65536`, which has a full period of
+    /// 65536, starting from a number drawn from this store's random
+    /// number generator on the first call. A store created with
+    /// `with_seed()` therefore always issues the same order.
+    pub fn next_sequential_u16(&mut self) -> &String {
+        let (mut x, mut remaining) = match self.sequence_u16 {
+            Some(state) => state,
+            None => (self.rng.next_u32() as u16, 1 << 16),
+        };
+        while remaining > 0 {
+            let id = number_to_uid(x as usize);
+            x = x.wrapping_mul(25173).wrapping_add(13849);
+            remaining -= 1;
+            if !self.items.contains(&id) {
+                self.sequence_u16 = Some((x, remaining));
+                self.generated += 1;
+                return self.insert_new(&id);
             }
-            return self.items.get(&id).unwrap();
+            self.collided(&id);
         }
+        self.sequence_u16 = Some((x, 0));
+        panic!("unable to generate a unique UID: {}", UidError::Exhausted);
     }
 
-    /// Generate a UID string that represents a random `u64` number.
-    /// The length of the string depends on the size of the number.
-    pub fn next_u64(&mut self) -> &String {
-        loop {
-            let id = random_max_size(u64::MAX as usize);
-            if !self.items.insert(id.clone()) {
-                continue;
+    /// Generate a UID string that represents the lowest `u16` number not
+    /// already in use, so every one of the 65536 numbers is used before
+    /// this returns None. Unlike `next_u16()` this never slows down as
+    /// the keyspace fills, but the UID's it issues are easy to guess.
+    /// Released UID's are reused, lowest number first.
+    pub fn next_free_u16_sequential(&mut self) -> Option<&String> {
+        while self.free_u16 <= u16::MAX as u32 {
+            let id = number_to_uid(self.free_u16 as usize);
+            self.free_u16 += 1;
+            if !self.items.contains(&id) {
+                self.generated += 1;
+                return Some(self.insert_new(&id));
             }
-            return self.items.get(&id).unwrap();
         }
+        None
+    }
+
+    /// Generate a UID string that represents a random `u16` number using
+    /// the characters of `human_random_string()`. See `number_to_human_uid()`.
+    pub fn next_human_u16(&mut self) -> &String {
+        let result = self.insert_unique(max_number_attempts(u16::MAX.into()), |rng, buf| {
+            buf.push_str(&number_to_human_uid(random_up_to(u16::MAX as usize, rng)))
+        });
+        expect_unique(result)
+    }
+
+    /// Generate a UID string that represents a random `u32` number using
+    /// the characters of `human_random_string()`. See `number_to_human_uid()`.
+    pub fn next_human_u32(&mut self) -> &String {
+        let result = self.insert_unique(max_number_attempts(u32::MAX.into()), |rng, buf| {
+            buf.push_str(&number_to_human_uid(random_up_to(u32::MAX as usize, rng)))
+        });
+        expect_unique(result)
+    }
+
+    /// Generate a UID string that represents a random `u64` number using
+    /// the characters of `human_random_string()`. See `number_to_human_uid()`.
+    pub fn next_human_u64(&mut self) -> &String {
+        let result = self.insert_unique(max_number_attempts(u64::MAX.into()), |rng, buf| {
+            buf.push_str(&number_to_human_uid(random_up_to(u64::MAX as usize, rng)))
+        });
+        expect_unique(result)
+    }
+
+    /// Generate a UID string that represents a random `u128` number.
+    /// The length of the string depends on the size of the number.
+    pub fn next_u128(&mut self) -> &String {
+        self.next_bounded::<u128>()
+    }
+
+    /// Generate a UID string that represents a random number of type
+    /// `T`, such as `next_bounded::<u16>()`, from 0 to `T::MAX`
+    /// inclusive. The length of the string depends on the size of the
+    /// number.
+    pub fn next_bounded<T: Bounded>(&mut self) -> &String {
+        let max: u128 = T::MAX.into();
+        let result = self.insert_unique(max_number_attempts(max), |rng, buf| {
+            let id = match usize::try_from(max) {
+                Ok(max) => generate_max_size(max, rng),
+                Err(_) => match max.checked_add(1) {
+                    Some(range) => number_to_uid_u128(generate_u128(rng) % range),
+                    None => number_to_uid_u128(generate_u128(rng)),
+                },
+            };
+            buf.push_str(&id);
+        });
+        expect_unique(result)
+    }
+
+    /// Returns how many of the 65536 UID's that `next_u16()` can
+    /// generate are not in use. This counts the stored UID's that
+    /// `uid_to_number()` decodes to a number no larger than `u16::MAX`,
+    /// so it takes time proportional to the size of the store.
+    pub fn remaining_u16(&self) -> usize {
+        self.remaining_below(u16::MAX as usize)
+    }
+
+    /// Returns how many of the 2^32 UID's that `next_u32()` can
+    /// generate are not in use. See `remaining_u16()`.
+    pub fn remaining_u32(&self) -> usize {
+        self.remaining_below(u32::MAX as usize)
+    }
+
+    fn remaining_below(&self, maximum_size: usize) -> usize {
+        let used: HashSet<usize> = self
+            .items
+            .iter()
+            .filter_map(|id| uid_to_number(id))
+            .filter(|n| *n <= maximum_size)
+            .collect();
+        // Wrapping keeps this correct when every number is used and
+        // avoids overflow when `maximum_size` is `usize::MAX`.
+        maximum_size.wrapping_sub(used.len()).wrapping_add(1)
     }
 
     /// Returns true if a UID is already in use.
@@ -145,184 +944,2262 @@ impl UidStore {
         self.items.contains(id)
     }
 
+    /// Returns the UID's in `uids` that are already in use, in the
+    /// order they appear in `uids`.
+    pub fn contains_any<'a>(&self, uids: &'a [&'a str]) -> Vec<&'a str> {
+        uids.iter()
+            .copied()
+            .filter(|id| self.items.contains(*id))
+            .collect()
+    }
+
     /// Returns how many UID's have already been used.
     pub fn size(&self) -> usize {
         self.items.len()
     }
 
-    /// Register a UID with this `UidStore`. Returns `None` if this
-    /// string is unique and not previously seen. If the string is
-    /// already known and in use, a new uid string is returned.
-    pub fn make_unique(&mut self, uid: &str) -> Option<&str> {
-        if self.items.contains(uid) {
-            return Some(self.next(uid.len()));
-        }
-        self.items.insert(uid.to_string());
-        None
+    /// Returns how many UID's this `UidStore` has generated over its
+    /// lifetime, as `issued_total()` does, saturating at `usize::MAX`.
+    pub fn total_generated(&self) -> usize {
+        usize::try_from(self.issued_total()).unwrap_or(usize::MAX)
     }
 
-    /// Register a UID with this `UidStore`. Returns `None` if this
-    /// string is unique and not previously seen. If the string is
-    /// already known and in use, a new uid string is returned.
-    pub fn make_unique_u16(&mut self, uid: &str) -> Option<&str> {
-        if self.items.contains(uid) {
-            return Some(self.next_u16());
-        }
-        self.items.insert(uid.to_string());
-        None
+    /// Returns how many UID's this `UidStore` has issued over its
+    /// lifetime, including those generated by `next()` and its variants
+    /// and those registered by `commit()`. UID's registered with
+    /// `make_unique` are only counted if a replacement had to be
+    /// generated. Unlike `size()` this count is not reduced by
+    /// `remove()`, `clear()` or any other removal, so it suits metrics
+    /// of how many UID's were ever issued.
+    pub fn issued_total(&self) -> u64 {
+        self.generated
     }
-}
 
-/// Generate a random base62 string with a fixed string `length`.
-pub fn random_string(length: usize) -> String {
-    let result: String = (0..length)
-        .map(|_| {
-            let idx = random::next_u32() as usize % CHARSET.len();
-            CHARSET[idx] as char
-        })
-        .collect();
+    /// Returns how many times a generated UID was discarded because it
+    /// was already in use. A high count relative to `total_generated()`
+    /// suggests that a longer UID length should be used.
+    pub fn collision_count(&self) -> usize {
+        self.collisions
+    }
 
-    result
-}
+    /// Set the shortest length of a UID generated by `make_unique()` to
+    /// replace one already in use. Without this a replacement has the
+    /// same length as the UID it replaces, so a collision on a short UID
+    /// is retried in the same cramped keyspace. Defaults to zero.
+    pub fn set_min_regen_length(&mut self, length: usize) {
+        self.min_regen_length = length;
+    }
 
-/// Generate a string of numbers with the specified `length`.
-pub fn random_number(length: usize) -> String {
-    let result: String = (0..length)
-        .map(|_| {
-            let idx = random::next_u32() as usize % NUMSET.len();
-            NUMSET[idx] as char
-        })
-        .collect();
+    /// Limit how many times each call that generates a UID may retry
+    /// after generating one already in use. Once the limit is passed,
+    /// methods such as `try_next()` return `UidError::RetryLimit` and
+    /// methods such as `next()`, `next_human()` and `next_u16()` panic.
+    /// Use this so a keyspace that is too small fails quickly rather
+    /// than slowing every call. Defaults to `usize::MAX`, which places
+    /// no limit beyond those the methods already have.
+    pub fn set_max_retries(&mut self, retries: usize) {
+        self.max_retries = retries;
+    }
 
-    result
-}
+    /// Call `f` with each generated UID that is found to be in use
+    /// already, just before another is generated in its place, for
+    /// example to log collisions as a keyspace fills. Replaces any
+    /// callback set before. Clones and forks of this store share the
+    /// callback.
+    ///
+    /// The callback runs inside the retry loop, which can call it many
+    /// times in a row as a keyspace fills, so it should be cheap.
+    ///
+    /// ```rust
+    /// # use uid_store::*;
+    /// let mut u = UidStore::new();
+    /// u.on_collision(Box::new(|id| eprintln!("uid {} was in use", id)));
+    /// ```
+    pub fn on_collision(&mut self, f: Box<dyn FnMut(&str) + Send>) {
+        self.on_collision = Some(CollisionHook(Arc::new(Mutex::new(f))));
+    }
 
-/// Generate a base62 string using a random number
-/// no larger than a specified maximum size.
-pub fn random_max_size(maximum_size: usize) -> String {
-    if maximum_size > u32::MAX as usize {
-        let uid = random::next_u64() as usize % maximum_size;
-        return number_to_uid(uid);
+    // Record that `id` was generated but already in use.
+    fn collided(&mut self, id: &str) {
+        self.collisions += 1;
+        if let Some(hook) = &self.on_collision {
+            (hook.0.lock().unwrap())(id);
+        }
     }
-    let uid = random::next_u32() as usize % maximum_size;
-    number_to_uid(uid)
-}
 
-/// Convert the contents of a base62 string back to
-/// the number that was used to generate the string.
-/// Reverse using `uid_to_number()`.
-pub fn number_to_uid(mut uid: usize) -> String {
-    let mut result = String::new();
-    if uid == 0 {
-        return "A".to_string();
+    /// Returns the limit set by `set_max_retries()`.
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
     }
-    while uid > 0 {
-        let next = uid % CHARSET.len();
-        uid = uid / CHARSET.len();
-        result.push(CHARSET[next] as char);
+
+    /// Returns the shortest length of a UID generated by `make_unique()`
+    /// to replace one already in use.
+    pub fn min_regen_length(&self) -> usize {
+        self.min_regen_length
     }
-    result
-}
 
-/// Convert a base62 string into the underlying number it
-/// represents. Returns None if the string is not a valid
-/// base62 number. Reverse using `number_to_uid()`.
-pub fn uid_to_number(uid: &str) -> Option<usize> {
-    let mut result: usize = 0;
-    for c in uid.chars().rev() {
-        /* Rust 1.18
-        let value = match c {
-            'A'..'Z' => c - 'A',
-            'a'..'z' => c - 'a' + 26,
-            '0'..'9' => c - '0' + 26 + 26,
-        };
-        */
-        let value;
-        if c >= 'A' && c <= 'Z' {
-            value = (c as usize) - ('A' as usize);
-        } else if c >= 'a' && c <= 'z' {
-            value = (c as usize) - ('a' as usize) as usize + 26;
-        } else if c >= '0' && c <= '9' {
-            value = (c as usize) - ('0' as usize) + 26 + 26;
-        } else {
+    /// Release a UID so that it may be generated again. Returns
+    /// true if the UID was in use.
+    pub fn remove(&mut self, id: &str) -> bool {
+        self.free_u16 = 0;
+        self.items.remove(id)
+    }
+
+    /// Replace the UID `old` with a new UID of `length` characters,
+    /// returning the new UID, or None if `old` was not in use. The new
+    /// UID is generated before `old` is released, so it can never be
+    /// the same as `old`. Panics in the same way as `next()`.
+    pub fn rotate(&mut self, old: &str, length: usize) -> Option<&String> {
+        if !self.items.contains(old) {
             return None;
         }
-        result = result * 62 + value;
+        let new = self.next(length).clone();
+        self.free_u16 = 0;
+        self.items.remove(old);
+        self.items.get(&new)
     }
-    Some(result)
-}
 
-/// Generate a random string that doedn't include easily confused
-/// characters such as i,I,1 and o,O,0.
-pub fn human_random_string(length: usize) -> String {
-    let result: String = (0..length)
-        .map(|_| {
-            let idx = random::next_u32() as usize % READABLE_CHARSET.len();
-            READABLE_CHARSET[idx] as char
-        })
-        .collect();
+    /// Mark the UID of every number from `start` up to but not including
+    /// `end` as used, so the numeric methods such as `next_u16()` never
+    /// generate them. Each number is stored as `number_to_uid()` writes it,
+    /// so this takes time and memory in proportion to the size of the
+    /// range; reserving millions of numbers costs as much as storing
+    /// millions of UID's. Does nothing if `end` is not above `start`.
+    pub fn reserve_number_range(&mut self, start: usize, end: usize) {
+        self.items.reserve(end.saturating_sub(start));
+        for n in start..end {
+            self.items.insert(number_to_uid(n));
+        }
+    }
 
-    result
-}
+    /// Returns true if the UID representing the number `n` is already
+    /// in use. As well as the string produced by `number_to_uid()`, this
+    /// matches strings padded with trailing `A` characters, such as those
+    /// produced by `number_to_uid_padded()`, which decode to the same
+    /// number.
+    pub fn contains_number(&self, n: usize) -> bool {
+        number_variants(n).any(|id| self.items.contains(&id))
+    }
 
-const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
-abcdefghijklmnopqrstuvwxyz\
-0123456789";
+    /// Release the UID representing the number `n`, including any padded
+    /// variants matched by `contains_number()`. Returns true if a UID was
+    /// in use.
+    pub fn remove_number(&mut self, n: usize) -> bool {
+        self.free_u16 = 0;
+        let mut removed = false;
+        for id in number_variants(n) {
+            removed |= self.items.remove(&id);
+        }
+        removed
+    }
 
-const READABLE_CHARSET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ\
-abcdefghjkmnpqrstuvwxyz\
-123456789";
+    /// Add every UID held by `other` to this `UidStore`. Returns the
+    /// UID's that were already held by both stores.
+    pub fn merge(&mut self, other: &UidStore) -> Vec<String> {
+        let mut collisions = Vec::new();
+        for id in &other.items {
+            if !self.items.insert(id.clone()) {
+                collisions.push(id.clone());
+            }
+        }
+        collisions
+    }
 
-const NUMSET: &[u8] = b"0123456789";
+    /// Returns true if no UID is held by both stores.
+    pub fn is_disjoint(&self, other: &UidStore) -> bool {
+        self.items.is_disjoint(&other.items)
+    }
 
-#[cfg(test)]
-mod tests {
-    use crate::human_random_string;
-    use crate::number_to_uid;
-    use crate::random_number;
-    use crate::random_string;
-    use crate::uid_to_number;
-    use crate::UidStore;
+    /// Returns how many UID's this `UidStore` can hold before it
+    /// needs to reallocate.
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
 
-    #[test]
-    fn test_number_to_uid() {
-        assert_eq!(number_to_uid(0), "A");
-        assert_eq!(number_to_uid(1), "B");
-        assert_eq!(number_to_uid(52), "0");
-        assert_eq!(number_to_uid(9902), "sjC");
-        assert_eq!(uid_to_number("A"), Some(0));
-        assert_eq!(uid_to_number("B"), Some(1));
-        assert_eq!(uid_to_number("0"), Some(52));
-        assert_eq!(uid_to_number("sjC"), Some(9902));
-        assert_eq!(uid_to_number(&number_to_uid(94029)), Some(94029));
-        assert_eq!(uid_to_number(&number_to_uid(2294029)), Some(2294029));
+    /// Returns an estimate of the heap memory used by the UID's this
+    /// `UidStore` holds, in bytes. This counts one `String` and one byte
+    /// of hash table metadata for each slot of `capacity()`, plus the
+    /// capacity of each stored string. Allocator overhead is not counted.
+    pub fn estimated_bytes(&self) -> usize {
+        let table = self.items.capacity() * (std::mem::size_of::<String>() + 1);
+        let strings: usize = self.items.iter().map(|id| id.capacity()).sum();
+        table + strings
+    }
+
+    /// Reserve space for at least `additional` more UID's.
+    pub fn reserve(&mut self, additional: usize) {
+        self.items.reserve(additional);
+    }
+
+    /// Release unused space, for example after many UID's
+    /// have been removed.
+    pub fn shrink_to_fit(&mut self) {
+        self.items.shrink_to_fit();
+    }
+
+    /// Keep only the UID's for which `f` returns true, releasing
+    /// all others.
+    pub fn retain<F: FnMut(&str) -> bool>(&mut self, mut f: F) {
+        self.free_u16 = 0;
+        self.items.retain(|id| f(id));
+    }
+
+    /// Reserve every UID starting with `prefix`, so that `next()` and
+    /// `next_human()` never generate one. UID's already in the store
+    /// are not affected. Reserving an empty prefix reserves every UID,
+    /// which causes those methods to panic.
+    pub fn reserve_prefix(&mut self, prefix: &str) {
+        self.reserved_prefixes.insert(prefix.to_string());
+    }
+
+    /// Returns the prefixes reserved with `reserve_prefix()`.
+    pub fn reserved_prefixes(&self) -> &HashSet<String> {
+        &self.reserved_prefixes
+    }
+
+    // Returns true if `uid` starts with a reserved prefix.
+    fn is_reserved(&self, uid: &str) -> bool {
+        self.reserved_prefixes
+            .iter()
+            .any(|p| uid.starts_with(p.as_str()))
+    }
+
+    /// Returns an iterator over every UID held by this `UidStore`,
+    /// without removing them. The order is unspecified.
+    pub fn iter(&self) -> impl Iterator<Item = &String> + '_ {
+        self.items.iter()
+    }
+
+    /// Returns a copy of every UID held by this `UidStore`, sorted
+    /// lexicographically by byte value. Unlike `iter()` the order is the
+    /// same on every run, which keeps saved lists easy to compare.
+    pub fn to_sorted_vec(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.items.iter().cloned().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Remove and return every UID held by this `UidStore`.
+    pub fn drain(&mut self) -> impl Iterator<Item = String> + '_ {
+        self.free_u16 = 0;
+        self.items.drain()
+    }
+
+    /// Release every UID held by this `UidStore`.
+    pub fn clear(&mut self) {
+        self.free_u16 = 0;
+        self.items.clear();
+    }
+
+    /// Register a UID with this `UidStore`. Returns `None` if this
+    /// string is unique and not previously seen. If the string is
+    /// already known and in use, a new uid string is returned with
+    /// the same length, or `min_regen_length()` if that is longer.
+    pub fn make_unique(&mut self, uid: &str) -> Option<&str> {
+        if self.try_claim(uid) {
+            return None;
+        }
+        let length = uid.len().max(self.min_regen_length);
+        Some(self.next(length))
+    }
+
+    /// Claim exactly the UID `uid`. Returns true if it was free and is
+    /// now in use, or false, leaving the store unchanged, if it was
+    /// already taken. Unlike `make_unique()` no replacement is generated.
+    pub fn try_claim(&mut self, uid: &str) -> bool {
+        // A `HashSet` can only insert an owned `String`, and the entry
+        // style `get_or_insert_with()` that would hash `uid` once and
+        // copy it only when missing is not yet stable. Checking first
+        // hashes twice on a miss but never copies a UID that is in use.
+        if self.items.contains(uid) {
+            return false;
+        }
+        self.items.insert(uid.to_string())
+    }
+
+    /// Register the UID `uid`, returning `AlreadyExists` and leaving the
+    /// store unchanged if it is already in use. Unlike `make_unique()`
+    /// no replacement is generated.
+    pub fn insert_strict(&mut self, uid: &str) -> Result<(), AlreadyExists> {
+        if self.try_claim(uid) {
+            Ok(())
+        } else {
+            Err(AlreadyExists)
+        }
+    }
+
+    /// Register each UID in `uids` with `make_unique()`, returning the
+    /// original and replacement of each UID that was already in use, in
+    /// the order they appear in `uids`. Unique UID's are registered but
+    /// not reported.
+    pub fn make_unique_batch(&mut self, uids: &[&str]) -> Vec<(String, String)> {
+        let mut replaced = Vec::new();
+        for uid in uids {
+            if let Some(replacement) = self.make_unique(uid) {
+                replaced.push((uid.to_string(), replacement.to_string()));
+            }
+        }
+        replaced
+    }
+
+    /// Register a UID with this `UidStore`, taking ownership of the
+    /// string to avoid copying it. Returns `None` if this string is
+    /// unique and not previously seen. If the string is already known
+    /// and in use, a new uid string is returned with the same length,
+    /// or `min_regen_length()` if that is longer.
+    pub fn make_unique_owned(&mut self, uid: String) -> Option<String> {
+        let length = uid.len().max(self.min_regen_length);
+        if self.items.insert(uid) {
+            return None;
+        }
+        Some(self.next(length).clone())
+    }
+
+    /// Register a UID with this `UidStore`. Returns `None` if this
+    /// string is unique and not previously seen. If the string is
+    /// already known and in use, a new uid string is returned.
+    pub fn make_unique_u16(&mut self, uid: &str) -> Option<&str> {
+        if self.try_claim(uid) {
+            return None;
+        }
+        Some(self.next_u16())
+    }
+
+    /// Register a UID with this `UidStore`. Returns `None` if this
+    /// string is unique and not previously seen. If the string is
+    /// already known and in use, a new uid string is returned.
+    pub fn make_unique_u32(&mut self, uid: &str) -> Option<&str> {
+        if self.try_claim(uid) {
+            return None;
+        }
+        Some(self.next_u32())
+    }
+
+    /// Register a UID with this `UidStore`. Returns `None` if this
+    /// string is unique and not previously seen. If the string is
+    /// already known and in use, a new uid string is returned.
+    pub fn make_unique_u64(&mut self, uid: &str) -> Option<&str> {
+        if self.try_claim(uid) {
+            return None;
+        }
+        Some(self.next_u64())
+    }
+}
+
+impl Default for UidStore {
+    fn default() -> Self {
+        UidStore::new()
+    }
+}
+
+impl PartialEq for UidStore {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+
+impl Eq for UidStore {}
+
+/// Build a `UidStore` from previously generated UID values.
+/// Duplicate values are only stored once.
+impl FromIterator<String> for UidStore {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        let mut store = UidStore::new();
+        store.extend(iter);
+        store
+    }
+}
+
+/// Consume a `UidStore`, returning the UID values it held.
+impl IntoIterator for UidStore {
+    type Item = String;
+    type IntoIter = std::collections::hash_set::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+/// Add previously generated UID values to a `UidStore`. Values
+/// already in the store are ignored rather than replaced.
+impl Extend<String> for UidStore {
+    fn extend<I: IntoIterator<Item = String>>(&mut self, iter: I) {
+        self.items.extend(iter);
+    }
+}
+
+/// A `UidStore` is serialized as a sequence of the UID's it holds.
+/// A store created with a seed is restored using the shared random
+/// number generator.
+#[cfg(feature = "serde")]
+impl serde::Serialize for UidStore {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.items)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for UidStore {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut store = UidStore::new();
+        store.items = HashSet::<String>::deserialize(deserializer)?;
+        Ok(store)
+    }
+}
+
+/// Generate a random base62 string with a fixed string `length`.
+pub fn random_string(length: usize) -> String {
+    generate(length, CHARSET, &mut Source::Global)
+}
+
+/// Generate a random string with a fixed string `length` using
+/// only characters found in `charset`. Returns an error if the
+/// `charset` is empty or contains a byte that is not ASCII, as each
+/// byte is used as one character.
+pub fn random_string_with_charset(length: usize, charset: &[u8]) -> Result<String, UidError> {
+    check_charset(charset)?;
+    Ok(generate(length, charset, &mut Source::Global))
+}
+
+/// Fill `buf` with random characters from `charset`, allowing a
+/// single buffer to be reused to avoid an allocation for each UID.
+///
+/// Panics if `charset` is empty.
+pub fn random_string_into(buf: &mut [u8], charset: &[u8]) {
+    assert!(!charset.is_empty(), "charset must not be empty");
+    let mut rng = Source::Global;
+    for c in buf.iter_mut() {
+        *c = charset[rng.next_u32() as usize % charset.len()];
+    }
+}
+
+/// Generate a random string with a fixed string `length` where each
+/// character of `charset` appears with a relative frequency given by
+/// the matching entry in `weights`. A character with a weight of 0 is
+/// never used. Returns an error if `charset` is empty or not ASCII, if
+/// `weights` has a different length, or if every weight is 0.
+///
+/// ```rust
+/// # use uid_store::*;
+/// // Digits are four times as likely as letters.
+/// let id = random_weighted_string(8, b"AB01", &[1, 1, 4, 4]).unwrap();
+/// assert_eq!(id.len(), 8);
+/// ```
+pub fn random_weighted_string(
+    length: usize,
+    charset: &[u8],
+    weights: &[u32],
+) -> Result<String, UidError> {
+    check_charset(charset)?;
+    if weights.len() != charset.len() {
+        return Err(UidError::InvalidWeights);
+    }
+    // The running total of the weights, a character is chosen by
+    // finding the first total greater than a random value below the
+    // sum of every weight.
+    let cumulative: Vec<u64> = weights
+        .iter()
+        .scan(0u64, |total, &w| {
+            *total += w as u64;
+            Some(*total)
+        })
+        .collect();
+    let total = cumulative[cumulative.len() - 1];
+    if total == 0 {
+        return Err(UidError::InvalidWeights);
+    }
+    let mut rng = Source::Global;
+    Ok((0..length)
+        .map(|_| {
+            let r = rng.next_u64() % total;
+            charset[cumulative.partition_point(|&c| c <= r)] as char
+        })
+        .collect())
+}
+
+/// Generate a random base62 string with a fixed string `length` where
+/// no character appears twice in a row, so runs like `AAA` that can
+/// look like a typing mistake never appear.
+pub fn random_string_no_repeats(length: usize) -> String {
+    generate_no_repeats(length, CHARSET, &mut Source::Global)
+}
+
+/// Generate a random string in the same way as
+/// `random_string_no_repeats()` using only characters found in
+/// `charset`. Returns an error if `charset` has fewer than two
+/// characters or is not ASCII.
+pub fn random_string_no_repeats_with_charset(
+    length: usize,
+    charset: &[u8],
+) -> Result<String, UidError> {
+    check_charset(charset)?;
+    if charset.len() < 2 {
+        return Err(UidError::CharsetTooSmall);
+    }
+    Ok(generate_no_repeats(length, charset, &mut Source::Global))
+}
+
+/// Returns `n` random bytes from the same generator used for UID's.
+///
+/// This generator is not cryptographically secure, so these bytes must
+/// not be used for keys, passwords or anything an attacker could
+/// benefit from predicting.
+pub fn random_bytes(n: usize) -> Vec<u8> {
+    let mut buf = vec![0; n];
+    random_bytes_into(&mut buf);
+    buf
+}
+
+/// Fill `buf` with random bytes from the same generator used for UID's.
+/// Like `random_bytes()` this is not cryptographically secure.
+pub fn random_bytes_into(buf: &mut [u8]) {
+    let mut rng = Source::Global;
+    for chunk in buf.chunks_mut(8) {
+        let bytes = rng.next_u64().to_le_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+}
+
+/// Generate a random string with a fixed string `length` using
+/// only lower case letters and numbers. Use this when UID's are
+/// stored by a system that ignores case.
+pub fn random_lower_string(length: usize) -> String {
+    generate(length, LOWERCASE_CHARSET, &mut Source::Global)
+}
+
+/// Generate a random string with a fixed string `length` using
+/// only upper case letters and numbers. Use this when UID's are
+/// stored by a system that ignores case.
+pub fn random_upper_string(length: usize) -> String {
+    generate(length, UPPERCASE_CHARSET, &mut Source::Global)
+}
+
+/// Generate a random base62 string with a fixed string `length` and
+/// convert it to upper case.
+///
+/// Each letter is twice as likely as each digit, so the string carries
+/// less entropy than one from `random_upper_string()`, which draws
+/// evenly from the same 36 characters. Prefer that function when the
+/// string is stored by a system that ignores case. Use this one only to
+/// match the casing convention of another system, where `a8K` and `A8K`
+/// would otherwise be distinct.
+pub fn random_string_upper(length: usize) -> String {
+    random_string(length).to_ascii_uppercase()
+}
+
+/// Generate a random base62 string with a fixed string `length` and
+/// convert it to lower case. The same entropy caveat as
+/// `random_string_upper()` applies, see `random_lower_string()`.
+pub fn random_string_lower(length: usize) -> String {
+    random_string(length).to_ascii_lowercase()
+}
+
+/// Generate a string of numbers with the specified `length`.
+pub fn random_number(length: usize) -> String {
+    generate(length, NUMSET, &mut Source::Global)
+}
+
+/// Generate a base62 string using a random number
+/// no larger than a specified maximum size. The maximum itself may be
+/// generated, so `random_max_size(1)` returns either `A` or `B`.
+pub fn random_max_size(maximum_size: usize) -> String {
+    generate_max_size(maximum_size, &mut Source::Global)
+}
+
+// Every string up to the width of `usize::MAX` that decodes to `n`
+// using `uid_to_number()`.
+fn number_variants(n: usize) -> impl Iterator<Item = String> {
+    let mut id = number_to_uid(n);
+    let count = SORTABLE_WIDTH.saturating_sub(id.len()) + 1;
+    (0..count).map(move |i| {
+        if i > 0 {
+            id.push(CHARSET[0] as char);
+        }
+        id.clone()
+    })
+}
+
+// Check that `charset` can be drawn from by `generate()`, which turns
+// each byte into one character.
+fn check_charset(charset: &[u8]) -> Result<(), UidError> {
+    if charset.is_empty() {
+        Err(UidError::EmptyCharset)
+    } else if !charset.is_ascii() {
+        Err(UidError::NonAsciiCharset)
+    } else {
+        Ok(())
+    }
+}
+
+// Unwrap the result of generating a UID, panicking with a clear
+// message if the keyspace is exhausted.
+fn expect_unique<T>(result: Result<T, UidError>) -> T {
+    match result {
+        Ok(id) => id,
+        Err(e) => panic!("unable to generate a unique UID: {}", e),
+    }
+}
+
+// The highest acceptable chance of a collision when estimating a safe
+// UID length.
+const COLLISION_PROBABILITY: f64 = 0.01;
+
+// The share of a keyspace in use at which `UidStore::is_saturated()`
+// reports it as full.
+const SATURATION: f64 = 0.9;
+
+// How many generated UID's in a row may be rejected, for example by a
+// blocklist, before giving up.
+const MAX_REJECTIONS: usize = 10_000;
+
+// How many times larger than the keyspace the number of attempts
+// to find an unused UID may be before giving up.
+const RETRY_FACTOR: usize = 32;
+
+// The number of attempts allowed to find an unused UID of `length`
+// characters drawn from a charset of `charset_size` characters.
+fn max_attempts(length: usize, charset_size: usize) -> usize {
+    keyspace(length, charset_size).saturating_mul(RETRY_FACTOR)
+}
+
+// The number of attempts allowed to find an unused UID for a number
+// from 0 to `max` inclusive.
+fn max_number_attempts(max: u128) -> usize {
+    usize::try_from(max)
+        .ok()
+        .and_then(|max| max.checked_add(1))
+        .unwrap_or(usize::MAX)
+        .saturating_mul(RETRY_FACTOR)
+}
+
+// The number of possible strings of `length` characters drawn from a
+// charset of `charset_size` characters, saturating at `usize::MAX`.
+fn keyspace(length: usize, charset_size: usize) -> usize {
+    u32::try_from(length)
+        .ok()
+        .and_then(|length| charset_size.checked_pow(length))
+        .unwrap_or(usize::MAX)
+}
+
+fn generate(length: usize, charset: &[u8], rng: &mut Source) -> String {
+    let mut result = String::new();
+    generate_into(length, charset, rng, &mut result);
+    result
+}
+
+// Append `length` characters drawn from `charset` to `buf`.
+fn generate_into(length: usize, charset: &[u8], rng: &mut Source, buf: &mut String) {
+    buf.reserve(length);
+    for _ in 0..length {
+        let idx = rng.next_u32() as usize % charset.len();
+        buf.push(charset[idx] as char);
+    }
+}
+
+// Generate a string where each character differs from the one before
+// it. After the first character one fewer choice is drawn from and the
+// previous character is skipped over, which is the same as drawing
+// again until a different character comes up, without the retries.
+fn generate_no_repeats(length: usize, charset: &[u8], rng: &mut Source) -> String {
+    let mut result = String::new();
+    generate_no_repeats_into(length, charset, rng, &mut result);
+    result
+}
+
+fn generate_no_repeats_into(length: usize, charset: &[u8], rng: &mut Source, buf: &mut String) {
+    let mut previous = None;
+    buf.extend((0..length).map(|_| {
+        let idx = match previous {
+            None => rng.next_u32() as usize % charset.len(),
+            Some(p) => {
+                let idx = rng.next_u32() as usize % (charset.len() - 1);
+                if idx >= p {
+                    idx + 1
+                } else {
+                    idx
+                }
+            }
+        };
+        previous = Some(idx);
+        charset[idx] as char
+    }));
+}
+
+fn generate_max_size(maximum_size: usize, rng: &mut Source) -> String {
+    number_to_uid(random_up_to(maximum_size, rng))
+}
+
+// A random number no larger than `maximum_size`.
+fn random_up_to(maximum_size: usize, rng: &mut Source) -> usize {
+    let uid = if maximum_size > u32::MAX as usize {
+        rng.next_u64() as usize
+    } else {
+        rng.next_u32() as usize
+    };
+    // The maximum size itself may be generated.
+    match maximum_size.checked_add(1) {
+        Some(range) => uid % range,
+        None => uid,
+    }
+}
+
+fn generate_u128(rng: &mut Source) -> u128 {
+    ((rng.next_u64() as u128) << 64) | rng.next_u64() as u128
+}
+
+/// Convert the contents of a base62 string back to
+/// the number that was used to generate the string.
+/// Reverse using `uid_to_number()`.
+pub fn number_to_uid(uid: usize) -> String {
+    number_to_radix(uid, CHARSET.len() as u32)
+}
+
+/// Convert a number to a string using only the characters of
+/// `human_random_string()`, which avoids easily confused characters.
+/// The radix is the number of readable characters, 55, rather than 62,
+/// so the result must be decoded with `human_uid_to_number()`, not
+/// `uid_to_number()`.
+pub fn number_to_human_uid(uid: usize) -> String {
+    encode_alphabet(uid, READABLE_CHARSET)
+}
+
+/// Convert a string created by `number_to_human_uid()` back to the
+/// number it represents. Returns None if the string is empty, contains
+/// a character not used by `human_random_string()`, or the number is
+/// too large for a `usize`.
+pub fn human_uid_to_number(uid: &str) -> Option<usize> {
+    decode_alphabet(uid, READABLE_CHARSET)
+}
+
+/// Convert a number to Crockford base32, written most significant digit
+/// first like an ordinary number. The alphabet leaves out I, L, O and U
+/// so codes can be read aloud or copied by hand without confusion.
+///
+/// ```rust
+/// # use uid_store::*;
+/// assert_eq!(number_to_crockford(0), "0");
+/// assert_eq!(number_to_crockford(32), "10");
+/// assert_eq!(number_to_crockford(1234), "16J");
+/// ```
+pub fn number_to_crockford(uid: usize) -> String {
+    encode_alphabet_msb(uid, CROCKFORD_CHARSET)
+}
+
+/// Convert a Crockford base32 string back to the number it represents.
+/// Decoding is lenient in the way Crockford describes: lower case is
+/// accepted, I and L are read as 1, O is read as 0, and hyphens are
+/// ignored. Returns None if the string has no digits, contains any other
+/// character, or the number is too large for a `usize`.
+pub fn crockford_to_number(uid: &str) -> Option<usize> {
+    let mut result: usize = 0;
+    let mut digits = 0;
+    for c in uid.bytes() {
+        let c = match c.to_ascii_uppercase() {
+            b'-' => continue,
+            b'I' | b'L' => b'1',
+            b'O' => b'0',
+            c => c,
+        };
+        let value = CROCKFORD_CHARSET.iter().position(|a| *a == c)?;
+        result = result
+            .checked_mul(CROCKFORD_CHARSET.len())?
+            .checked_add(value)?;
+        digits += 1;
+    }
+    if digits == 0 {
+        return None;
+    }
+    Some(result)
+}
+
+/// Convert a number to base58 using the Bitcoin alphabet, written most
+/// significant digit first. The alphabet leaves out 0, O, I and l.
+///
+/// ```rust
+/// # use uid_store::*;
+/// assert_eq!(number_to_base58(0), "1");
+/// assert_eq!(number_to_base58(57), "z");
+/// assert_eq!(number_to_base58(58), "21");
+/// ```
+pub fn number_to_base58(uid: usize) -> String {
+    encode_alphabet_msb(uid, BASE58_CHARSET)
+}
+
+/// Convert a base58 string using the Bitcoin alphabet back to the
+/// number it represents. Returns None if the string is empty, contains
+/// a character outside the alphabet, or the number is too large for a
+/// `usize`.
+pub fn base58_to_number(uid: &str) -> Option<usize> {
+    if uid.is_empty() {
+        return None;
+    }
+    let mut result: usize = 0;
+    for c in uid.bytes() {
+        let value = BASE58_CHARSET.iter().position(|a| *a == c)?;
+        result = result
+            .checked_mul(BASE58_CHARSET.len())?
+            .checked_add(value)?;
+    }
+    Some(result)
+}
+
+/// Convert `bytes` to a base62 string, treating them as one big endian
+/// number of any size and writing the most significant digit first.
+/// Each leading zero byte is written as a leading `A`, so no bytes are
+/// lost. Reverse using `base62_to_bytes()`.
+///
+/// This does not use the same digit order as `number_to_uid()`, which
+/// writes the least significant digit first.
+///
+/// ```rust
+/// # use uid_store::*;
+/// assert_eq!(bytes_to_base62(&[1, 0]), "EI");
+/// assert_eq!(bytes_to_base62(&[0, 0, 5]), "AAF");
+/// assert_eq!(base62_to_bytes("AAF"), Some(vec![0, 0, 5]));
+/// ```
+pub fn bytes_to_base62(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    // The base62 digits of the number, least significant first.
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 4 / 3 + 1);
+    for byte in &bytes[zeros..] {
+        let mut carry = *byte as usize;
+        for digit in digits.iter_mut() {
+            carry += (*digit as usize) << 8;
+            *digit = (carry % CHARSET.len()) as u8;
+            carry /= CHARSET.len();
+        }
+        while carry > 0 {
+            digits.push((carry % CHARSET.len()) as u8);
+            carry /= CHARSET.len();
+        }
+    }
+    let mut result = String::with_capacity(zeros + digits.len());
+    result.extend(std::iter::repeat_n(CHARSET[0] as char, zeros));
+    result.extend(digits.iter().rev().map(|d| CHARSET[*d as usize] as char));
+    result
+}
+
+/// Convert a string written by `bytes_to_base62()` back to the bytes it
+/// represents. Returns None if the string contains a character that is
+/// not base62. The empty string represents no bytes.
+pub fn base62_to_bytes(uid: &str) -> Option<Vec<u8>> {
+    let zeros = uid.bytes().take_while(|c| *c == CHARSET[0]).count();
+    // The bytes of the number, least significant first.
+    let mut bytes: Vec<u8> = Vec::with_capacity(uid.len() * 3 / 4 + 1);
+    for c in uid[zeros..].chars() {
+        let mut carry = base62_value(c)?;
+        for byte in bytes.iter_mut() {
+            carry += *byte as usize * CHARSET.len();
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes.resize(bytes.len() + zeros, 0);
+    bytes.reverse();
+    Some(bytes)
+}
+
+// Encode `uid` most significant digit first, using the position of
+// each character in `alphabet` as its value.
+fn encode_alphabet_msb(mut uid: usize, alphabet: &[u8]) -> String {
+    let mut result = Vec::new();
+    loop {
+        result.push(alphabet[uid % alphabet.len()]);
+        uid /= alphabet.len();
+        if uid == 0 {
+            break;
+        }
+    }
+    result.reverse();
+    String::from_utf8(result).unwrap()
+}
+
+/// Convert a number to a string in the same way as `number_to_uid()`,
+/// using `alphabet` in place of the base62 charset. The first character
+/// of `alphabet` represents zero, so a distinctive zero character can be
+/// chosen, and the radix is the length of `alphabet`.
+///
+/// ```rust
+/// # use uid_store::*;
+/// assert_eq!(number_to_uid_with_alphabet(0, b"_ab"), "_");
+/// assert_eq!(number_to_uid_with_alphabet(5, b"_ab"), "ba");
+/// assert_eq!(uid_with_alphabet_to_number("ba", b"_ab"), Some(5));
+/// ```
+///
+/// Panics if `alphabet` has fewer than 2 characters, repeats a
+/// character, or contains a character that is not ASCII.
+pub fn number_to_uid_with_alphabet(uid: usize, alphabet: &[u8]) -> String {
+    assert!(
+        is_valid_alphabet(alphabet),
+        "alphabet must have at least 2 distinct ASCII characters"
+    );
+    encode_alphabet(uid, alphabet)
+}
+
+/// Convert a string created by `number_to_uid_with_alphabet()` back to
+/// the number it represents, using the same `alphabet`. Returns None if
+/// the alphabet is not valid, the string is empty or contains a
+/// character outside the alphabet, or the number is too large for a
+/// `usize`.
+pub fn uid_with_alphabet_to_number(uid: &str, alphabet: &[u8]) -> Option<usize> {
+    if !is_valid_alphabet(alphabet) {
+        return None;
+    }
+    decode_alphabet(uid, alphabet)
+}
+
+fn is_valid_alphabet(alphabet: &[u8]) -> bool {
+    alphabet.len() >= 2
+        && alphabet.is_ascii()
+        && alphabet
+            .iter()
+            .enumerate()
+            .all(|(i, c)| !alphabet[..i].contains(c))
+}
+
+// Encode `uid` least significant digit first, using the position of
+// each character in `alphabet` as its value.
+fn encode_alphabet(mut uid: usize, alphabet: &[u8]) -> String {
+    let mut result = String::new();
+    loop {
+        result.push(alphabet[uid % alphabet.len()] as char);
+        uid /= alphabet.len();
+        if uid == 0 {
+            return result;
+        }
+    }
+}
+
+// Reverse `encode_alphabet()`.
+fn decode_alphabet(uid: &str, alphabet: &[u8]) -> Option<usize> {
+    if uid.is_empty() {
+        return None;
+    }
+    let mut result: usize = 0;
+    for c in uid.bytes().rev() {
+        let value = alphabet.iter().position(|a| *a == c)?;
+        result = result.checked_mul(alphabet.len())?.checked_add(value)?;
+    }
+    Some(result)
+}
+
+/// Convert a number to a string using the first `radix` characters
+/// of the base62 charset as digits, so radix 16 uses `A` to `P`.
+/// Digits are written in the same order as `number_to_uid()`, which
+/// is the radix 62 case. Reverse using `radix_to_number()`.
+///
+/// Panics if `radix` is not between 2 and 62.
+pub fn number_to_radix(mut uid: usize, radix: u32) -> String {
+    assert!(
+        (2..=CHARSET.len() as u32).contains(&radix),
+        "radix must be between 2 and 62"
+    );
+    let radix = radix as usize;
+    let mut result = String::new();
+    if uid == 0 {
+        return (CHARSET[0] as char).to_string();
+    }
+    while uid > 0 {
+        let next = uid % radix;
+        uid /= radix;
+        result.push(CHARSET[next] as char);
+    }
+    result
+}
+
+/// Convert a string created by `number_to_radix()` back to the number
+/// it represents. Returns None if `radix` is not between 2 and 62, the
+/// string is empty or contains a character that is not a digit in this
+/// radix, or the number is too large for a `usize`.
+pub fn radix_to_number(uid: &str, radix: u32) -> Option<usize> {
+    if !(2..=CHARSET.len() as u32).contains(&radix) || uid.is_empty() {
+        return None;
+    }
+    let radix = radix as usize;
+    let mut result: usize = 0;
+    for c in uid.chars().rev() {
+        let value = base62_value(c).filter(|value| *value < radix)?;
+        result = result.checked_mul(radix)?.checked_add(value)?;
+    }
+    Some(result)
+}
+
+/// Convert a number to a base62 string that is always `width`
+/// characters long. `number_to_uid()` writes the least significant
+/// digit first, so padding with the zero character `A` is added to
+/// the end of the string and `uid_to_number()` decodes it unchanged.
+/// Returns an error if the number needs more than `width` characters.
+pub fn number_to_uid_padded(uid: usize, width: usize) -> Result<String, UidError> {
+    let mut result = number_to_uid(uid);
+    if result.len() > width {
+        return Err(UidError::Overflow);
+    }
+    while result.len() < width {
+        result.push(CHARSET[0] as char);
+    }
+    Ok(result)
+}
+
+/// Returns the value of each base62 digit of `uid`, from 0 to 61, most
+/// significant first. `number_to_uid()` writes the least significant
+/// digit first, so the digits are in the reverse order of the
+/// characters. Returns None if the string is empty or contains a
+/// character that is not base62.
+///
+/// ```rust
+/// # use uid_store::*;
+/// assert_eq!(uid_digits(&number_to_uid(62 * 3 + 5)), Some(vec![3, 5]));
+/// ```
+pub fn uid_digits(uid: &str) -> Option<Vec<usize>> {
+    if uid.is_empty() {
+        return None;
+    }
+    uid.chars().rev().map(base62_value).collect()
+}
+
+/// Convert a number from a keyspace of `0..=max` to a base62 string as
+/// wide as `number_to_uid(max)`, so every number in the keyspace has the
+/// same width. Padding is added as described by `number_to_uid_padded()`
+/// and `uid_to_number()` decodes the result unchanged. For example use
+/// `u16::MAX as usize` as `max` to give the numbers generated by
+/// `UidStore::next_u16()` a single width.
+///
+/// Panics if `uid` is greater than `max`.
+pub fn number_to_uid_fixed(uid: usize, max: usize) -> String {
+    assert!(uid <= max, "uid must not be greater than max");
+    number_to_uid_padded(uid, uid_length_for(max)).unwrap()
+}
+
+/// Returns the number of characters in the string `number_to_uid()`
+/// produces for `uid`, without creating the string.
+pub fn uid_length_for(mut uid: usize) -> usize {
+    let mut length = 1;
+    while uid >= CHARSET.len() {
+        uid /= CHARSET.len();
+        length += 1;
+    }
+    length
+}
+
+/// Convert a number to a base62 string followed by a check character
+/// that allows typing mistakes to be detected. The check character is
+/// calculated with the Luhn mod N algorithm, which detects every
+/// single character substitution and most swapped adjacent characters.
+/// Reverse using `uid_to_number_checked()`.
+pub fn number_to_uid_checked(uid: usize) -> String {
+    let mut result = number_to_uid(uid);
+    let digits: Vec<usize> = result.chars().filter_map(base62_value).collect();
+    result.push(CHARSET[luhn_check_digit(&digits)] as char);
+    result
+}
+
+/// Convert a string created by `number_to_uid_checked()` back to the
+/// number it represents. Returns None if the check character does not
+/// match or the string is not a valid base62 number.
+pub fn uid_to_number_checked(uid: &str) -> Option<usize> {
+    let digits: Vec<usize> = uid.chars().map(base62_value).collect::<Option<_>>()?;
+    let (check, payload) = digits.split_last()?;
+    if payload.is_empty() || luhn_check_digit(payload) != *check {
+        return None;
+    }
+    uid_to_number(&uid[..payload.len()])
+}
+
+// Luhn mod N check digit for a sequence of base62 digits.
+fn luhn_check_digit(digits: &[usize]) -> usize {
+    let n = CHARSET.len();
+    let mut factor = 2;
+    let mut sum = 0;
+    for digit in digits.iter().rev() {
+        let addend = factor * digit;
+        sum += addend / n + addend % n;
+        factor = if factor == 2 { 1 } else { 2 };
+    }
+    (n - sum % n) % n
+}
+
+/// Convert a base62 string into the underlying number it
+/// represents. Returns None if the string is not a valid
+/// base62 number or is too large for a `usize`. Reverse using
+/// `number_to_uid()`.
+///
+/// The empty string is not a valid base62 number, zero is
+/// always written as `A` by `number_to_uid()`.
+pub fn uid_to_number(uid: &str) -> Option<usize> {
+    radix_to_number(uid, CHARSET.len() as u32)
+}
+
+/// Convert a base62 string into the underlying number it represents,
+/// returning None unless the string is valid and the number is no
+/// larger than `max`. Use `u16::MAX as usize` for example to accept
+/// only UID's produced by `UidStore::next_u16()`.
+pub fn uid_to_number_bounded(uid: &str, max: usize) -> Option<usize> {
+    uid_to_number(uid).filter(|n| *n <= max)
+}
+
+/// Convert a base62 string typed or pasted by a person back to the
+/// number it represents, ignoring any whitespace before or after it.
+/// Otherwise this is as strict as `uid_to_number()`.
+pub fn uid_to_number_lenient(uid: &str) -> Option<usize> {
+    uid_to_number(uid.trim())
+}
+
+/// Convert a base62 string to a number in the same way as
+/// `uid_to_number_lenient()`, treating lower case letters as if they
+/// were upper case.
+///
+/// Base62 relies on case, so this decodes `abc`, `ABC` and `aBc` to the
+/// same number, the number of `ABC`. Only use this when UID's are known
+/// to be written without lower case letters, such as those from
+/// `random_upper_string()`, otherwise two different UID's can decode to
+/// the same number.
+pub fn uid_to_number_ignore_case(uid: &str) -> Option<usize> {
+    uid_to_number(&uid.trim().to_ascii_uppercase())
+}
+
+/// Convert a `u128` number to a base62 string. This uses the same
+/// encoding as `number_to_uid()`. Reverse using `uid_to_number_u128()`.
+pub fn number_to_uid_u128(mut uid: u128) -> String {
+    let mut result = String::new();
+    if uid == 0 {
+        return "A".to_string();
+    }
+    while uid > 0 {
+        let next = (uid % CHARSET.len() as u128) as usize;
+        uid /= CHARSET.len() as u128;
+        result.push(CHARSET[next] as char);
+    }
+    result
+}
+
+/// Convert a base62 string into the underlying `u128` number it
+/// represents. Returns None if the string is empty, is not a valid
+/// base62 number or is too large for a `u128`.
+pub fn uid_to_number_u128(uid: &str) -> Option<u128> {
+    if uid.is_empty() {
+        return None;
+    }
+    let mut result: u128 = 0;
+    for c in uid.chars().rev() {
+        result = result
+            .checked_mul(CHARSET.len() as u128)?
+            .checked_add(base62_value(c)? as u128)?;
+    }
+    Some(result)
+}
+
+/// Convert a signed number to a base62 string. Negative numbers are
+/// written as a `-` followed by the base62 encoding of the magnitude,
+/// other numbers use the same encoding as `number_to_uid()`. Reverse
+/// using `uid_to_number_signed()`.
+pub fn number_to_uid_signed(uid: i64) -> String {
+    let magnitude = number_to_uid_u128(uid.unsigned_abs() as u128);
+    if uid < 0 {
+        format!("-{}", magnitude)
+    } else {
+        magnitude
+    }
+}
+
+/// Convert a string created by `number_to_uid_signed()` back to the
+/// signed number it represents. Returns None if the string is not a
+/// valid base62 number, optionally preceded by `-`, or does not fit
+/// in an `i64`.
+pub fn uid_to_number_signed(uid: &str) -> Option<i64> {
+    match uid.strip_prefix('-') {
+        Some(magnitude) => {
+            let magnitude = uid_to_number_u128(magnitude)?;
+            if magnitude == 0 {
+                return None;
+            }
+            i64::try_from(-i128::try_from(magnitude).ok()?).ok()
+        }
+        None => i64::try_from(uid_to_number_u128(uid)?).ok(),
+    }
+}
+
+/// Returns true if `uid` is a non-empty string containing only base62
+/// characters. Unlike `uid_to_number()` this does not decode the string,
+/// so it also accepts UID's too long to fit in a number.
+pub fn is_valid_uid(uid: &str) -> bool {
+    !uid.is_empty() && uid.bytes().all(|c| CHARSET.contains(&c))
+}
+
+/// Returns true if `uid` is a non-empty string containing only the
+/// characters used by `human_random_string()`.
+pub fn is_valid_human_uid(uid: &str) -> bool {
+    !uid.is_empty() && uid.bytes().all(|c| READABLE_CHARSET.contains(&c))
+}
+
+// The value of a single base62 character.
+fn base62_value(c: char) -> Option<usize> {
+    /* Rust 1.18
+    let value = match c {
+        'A'..'Z' => c - 'A',
+        'a'..'z' => c - 'a' + 26,
+        '0'..'9' => c - '0' + 26 + 26,
+    };
+    */
+    if c.is_ascii_uppercase() {
+        Some((c as usize) - ('A' as usize))
+    } else if c.is_ascii_lowercase() {
+        Some((c as usize) - ('a' as usize) + 26)
+    } else if c.is_ascii_digit() {
+        Some((c as usize) - ('0' as usize) + 26 + 26)
+    } else {
+        None
+    }
+}
+
+/// Convert a number to a fixed width base62 string that sorts in
+/// the same order as the number it represents. Digits are written
+/// most significant first using the ASCII ordered alphabet `0-9A-Za-z`
+/// and padded with `0` to the width of `usize::MAX`. This is not the
+/// same encoding as `number_to_uid()`, reverse using
+/// `uid_to_number_sortable()`.
+pub fn number_to_uid_sortable(uid: usize) -> String {
+    encode_sortable(uid as u64, SORTABLE_WIDTH)
+}
+
+// Write the lowest `width` sortable digits of `uid`, most significant first.
+fn encode_sortable(mut uid: u64, width: usize) -> String {
+    let mut result = vec![SORTABLE_CHARSET[0]; width];
+    for c in result.iter_mut().rev() {
+        *c = SORTABLE_CHARSET[(uid % SORTABLE_CHARSET.len() as u64) as usize];
+        uid /= SORTABLE_CHARSET.len() as u64;
+    }
+    String::from_utf8(result).unwrap()
+}
+
+/// Generate a UID that sorts in the order it was created, similar to a
+/// ULID. The first 8 characters are the current time in milliseconds
+/// using the encoding of `number_to_uid_sortable()`, followed by 12
+/// random base62 characters. UID's created in the same millisecond
+/// are in random order.
+pub fn ulid_string() -> String {
+    generate_ulid(now_millis(), &mut Source::Global)
+}
+
+fn generate_ulid(millis: u64, rng: &mut Source) -> String {
+    let mut id = encode_sortable(millis, ULID_TIME_WIDTH);
+    id.push_str(&generate(ULID_RANDOM_LENGTH, CHARSET, rng));
+    id
+}
+
+/// Generate a random version 4 UUID in the canonical hyphenated form,
+/// such as `1b4e28ba-2fa1-4d2b-b83c-29a9ab4d1f7e`. The version and
+/// variant bits are set as described by RFC 9562 and the remaining 122
+/// bits are random. The generator is not cryptographically secure.
+pub fn uuid_v4_string() -> String {
+    generate_uuid_v4(&mut Source::Global)
+}
+
+fn generate_uuid_v4(rng: &mut Source) -> String {
+    let mut result = String::with_capacity(36);
+    generate_uuid_v4_into(rng, &mut result);
+    result
+}
+
+fn generate_uuid_v4_into(rng: &mut Source, buf: &mut String) {
+    use std::fmt::Write;
+
+    let mut bits = generate_u128(rng);
+    bits = (bits & !(0xf << 76)) | (0x4 << 76);
+    bits = (bits & !(0x3 << 62)) | (0x2 << 62);
+    write!(
+        buf,
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        bits >> 96,
+        (bits >> 80) & 0xffff,
+        (bits >> 64) & 0xffff,
+        (bits >> 48) & 0xffff,
+        bits & 0xffff_ffff_ffff
+    )
+    .unwrap();
+}
+
+fn now_millis() -> u64 {
+    match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(t) => t.as_millis() as u64,
+        Err(_) => 0,
+    }
+}
+
+/// Convert a string created by `number_to_uid_sortable()` back to
+/// the number it represents. Returns None if the string is not the
+/// expected width or contains characters outside the alphabet.
+pub fn uid_to_number_sortable(uid: &str) -> Option<usize> {
+    if uid.len() != SORTABLE_WIDTH {
+        return None;
+    }
+    let mut result: usize = 0;
+    for c in uid.chars() {
+        let value;
+        if c.is_ascii_digit() {
+            value = (c as usize) - ('0' as usize);
+        } else if c.is_ascii_uppercase() {
+            value = (c as usize) - ('A' as usize) + 10;
+        } else if c.is_ascii_lowercase() {
+            value = (c as usize) - ('a' as usize) + 10 + 26;
+        } else {
+            return None;
+        }
+        result = result
+            .checked_mul(SORTABLE_CHARSET.len())?
+            .checked_add(value)?;
+    }
+    Some(result)
+}
+
+/// Generate a random base62 string with a fixed string `length`
+/// using the operating system's cryptographically secure random
+/// number generator.
+#[cfg(feature = "secure")]
+pub fn random_string_secure(length: usize) -> String {
+    generate(length, CHARSET, &mut Source::Secure)
+}
+
+/// Generate a string of numbers with the specified `length` using
+/// the operating system's cryptographically secure random number
+/// generator.
+#[cfg(feature = "secure")]
+pub fn random_number_secure(length: usize) -> String {
+    generate(length, NUMSET, &mut Source::Secure)
+}
+
+/// Generate a random string that doesn't include easily confused
+/// characters using the operating system's cryptographically secure
+/// random number generator.
+#[cfg(feature = "secure")]
+pub fn human_random_string_secure(length: usize) -> String {
+    generate(length, READABLE_CHARSET, &mut Source::Secure)
+}
+
+/// Generate a random base62 string with a fixed string `length` that
+/// doesn't include any of the characters in `exclude`. This allows the
+/// set of easily confused characters to be tuned for a specific font.
+/// Returns an error if every base62 character is excluded.
+pub fn human_random_string_excluding(length: usize, exclude: &[u8]) -> Result<String, UidError> {
+    let charset: Vec<u8> = CHARSET
+        .iter()
+        .copied()
+        .filter(|c| !exclude.contains(c))
+        .collect();
+    random_string_with_charset(length, &charset)
+}
+
+/// Generate a random string that doedn't include easily confused
+/// characters such as i,I,1 and o,O,0.
+pub fn human_random_string(length: usize) -> String {
+    generate(length, READABLE_CHARSET, &mut Source::Global)
+}
+
+/// Generate a random base62 string of `total_len` characters split
+/// into groups of `group_size` characters by `separator`, such as
+/// `A8K2-9FJ3-QW1Z`. The separator is not counted in `total_len`. If
+/// `total_len` is not a multiple of `group_size` the last group is
+/// shorter.
+///
+/// Panics if `group_size` is zero or `separator` is a base62 character.
+pub fn grouped_string(total_len: usize, group_size: usize, separator: char) -> String {
+    generate_grouped(total_len, group_size, separator, &mut Source::Global)
+}
+
+fn generate_grouped(
+    total_len: usize,
+    group_size: usize,
+    separator: char,
+    rng: &mut Source,
+) -> String {
+    let mut result = String::new();
+    generate_grouped_into(total_len, group_size, separator, rng, &mut result);
+    result
+}
+
+fn generate_grouped_into(
+    total_len: usize,
+    group_size: usize,
+    separator: char,
+    rng: &mut Source,
+    buf: &mut String,
+) {
+    assert!(group_size > 0, "group_size must not be zero");
+    assert!(
+        !separator.is_ascii_alphanumeric(),
+        "separator must not be a base62 character"
+    );
+    for i in 0..total_len {
+        if i > 0 && i % group_size == 0 {
+            buf.push(separator);
+        }
+        buf.push(CHARSET[rng.next_u32() as usize % CHARSET.len()] as char);
+    }
+}
+
+/// Derive a base62 UID of `length` characters from `key`, such as an
+/// email address. The same key always gives the same UID, across runs
+/// and versions of this crate, so no table of keys to UID's needs to be
+/// kept. Different keys almost always give different UID's, although
+/// the key is hashed to 64 bits, so characters beyond the eleventh add
+/// no further protection against two keys sharing a UID.
+///
+/// The UID is not secret, anyone who knows the key can compute it.
+///
+/// ```rust
+/// # use uid_store::*;
+/// let uid = deterministic_uid("someone@example.com", 10);
+/// assert_eq!(uid, deterministic_uid("someone@example.com", 10));
+/// ```
+pub fn deterministic_uid(key: &str, length: usize) -> String {
+    deterministic_uid_in("", key, length)
+}
+
+/// Derive a base62 UID from `key` in the same way as
+/// `deterministic_uid()`, salted with `namespace`, so the same key
+/// gives unrelated UID's in different namespaces.
+pub fn deterministic_uid_in(namespace: &str, key: &str, length: usize) -> String {
+    let rng = PseudoRandom::new_with_namespaced_seed(namespace, key);
+    generate(length, CHARSET, &mut Source::Seeded(rng))
+}
+
+/// Generate a random string following `template`, where each `A` is
+/// replaced by a random letter, each `9` by a random digit and each `*`
+/// by any base62 character. Other characters are copied unchanged, so
+/// `"AA-999"` might produce `"Kp-472"`. To include a literal `A`, `9`,
+/// `*` or `\`, place a backslash before it, so `"\\A9"` produces an `A`
+/// followed by a random digit.
+///
+/// ```rust
+/// # use uid_store::*;
+/// let code = templated_string("AA-999");
+/// assert_eq!(code.len(), 6);
+/// assert_eq!(&code[2..3], "-");
+/// assert!(templated_string("\\A*").starts_with('A'));
+/// ```
+pub fn templated_string(template: &str) -> String {
+    generate_templated(&parse_template(template), &mut Source::Global)
+}
+
+// A single character of a template parsed by `parse_template()`.
+enum TemplatePart {
+    Literal(char),
+    Random(&'static [u8]),
+}
+
+fn parse_template(template: &str) -> Vec<TemplatePart> {
+    let mut parts = Vec::new();
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        parts.push(match c {
+            '\\' => TemplatePart::Literal(chars.next().unwrap_or('\\')),
+            'A' => TemplatePart::Random(LETTERS),
+            '9' => TemplatePart::Random(NUMSET),
+            '*' => TemplatePart::Random(CHARSET),
+            c => TemplatePart::Literal(c),
+        });
+    }
+    parts
+}
+
+fn generate_templated(parts: &[TemplatePart], rng: &mut Source) -> String {
+    let mut result = String::new();
+    generate_templated_into(parts, rng, &mut result);
+    result
+}
+
+fn generate_templated_into(parts: &[TemplatePart], rng: &mut Source, buf: &mut String) {
+    buf.extend(parts.iter().map(|part| match part {
+        TemplatePart::Literal(c) => *c,
+        TemplatePart::Random(charset) => charset[rng.next_u32() as usize % charset.len()] as char,
+    }));
+}
+
+/// Generate a random string that alternates between consonants and
+/// vowels, such as `bopuka`, making it easier to read aloud and to
+/// remember. The string starts with a consonant and is lower case.
+pub fn pronounceable_string(length: usize) -> String {
+    generate_pronounceable(length, &mut Source::Global)
+}
+
+fn generate_pronounceable(length: usize, rng: &mut Source) -> String {
+    let mut result = String::new();
+    generate_pronounceable_into(length, rng, &mut result);
+    result
+}
+
+fn generate_pronounceable_into(length: usize, rng: &mut Source, buf: &mut String) {
+    buf.extend((0..length).map(|i| {
+        let charset = if i % 2 == 0 { CONSONANTS } else { VOWELS };
+        charset[rng.next_u32() as usize % charset.len()] as char
+    }));
+}
+
+/// Returns the number of bits of entropy in a random string of
+/// `length` characters drawn from a charset of `charset_size`
+/// characters, calculated as `length * log2(charset_size)`.
+pub fn entropy_bits(length: usize, charset_size: usize) -> f64 {
+    length as f64 * (charset_size as f64).log2()
+}
+
+/// Returns the number of possible strings of `length` characters drawn
+/// from a charset of `charset_size` characters, that is `charset_size`
+/// to the power of `length`, or None if it is too large for a `u128`.
+///
+/// ```rust
+/// # use uid_store::*;
+/// let mut u = UidStore::new();
+/// u.next_many(1_000, 4);
+/// let keyspace = keyspace_size(4, 62).unwrap();
+/// assert_eq!(keyspace, 14_776_336);
+/// let used = u.size() as f64 / keyspace as f64;
+/// assert!(used < 0.0001);
+/// ```
+pub fn keyspace_size(length: usize, charset_size: usize) -> Option<u128> {
+    match u32::try_from(length) {
+        Ok(length) => (charset_size as u128).checked_pow(length),
+        // Only a charset of 0 or 1 characters has a keyspace that fits.
+        Err(_) if charset_size <= 1 => Some(charset_size as u128),
+        Err(_) => None,
+    }
+}
+
+/// Returns the number of possible base62 UID's of `length` characters,
+/// or None if it is too large for a `u128`. See `keyspace_size()`.
+pub fn keyspace_size_base62(length: usize) -> Option<u128> {
+    keyspace_size(length, CHARSET.len())
+}
+
+/// Generate a random base62 string with a fixed string `length`,
+/// returned with the number of bits of entropy it contains.
+pub fn random_string_bits(length: usize) -> (String, f64) {
+    (random_string(length), entropy_bits(length, CHARSET.len()))
+}
+
+/// Returns the shortest UID length that keeps the chance of any two of
+/// `expected_count` random UID's colliding below 1%, when each character
+/// is drawn from a charset of `charset_size` characters.
+///
+/// This uses the birthday bound `p ≈ n(n - 1) / 2k`, where `n` is the
+/// expected count and `k` is the number of possible UID's, that is
+/// `charset_size` to the power of the length. The length returned is the
+/// smallest for which `k >= n(n - 1) / 2p` with `p = 0.01`.
+///
+/// Panics if `charset_size` is less than 2.
+pub fn recommended_length(expected_count: usize, charset_size: usize) -> usize {
+    assert!(charset_size >= 2, "charset_size must be at least 2");
+    let n = expected_count as f64;
+    let required = n * (n - 1.0) / (2.0 * COLLISION_PROBABILITY);
+    let mut length = 1;
+    let mut keyspace = charset_size as f64;
+    while keyspace < required {
+        keyspace *= charset_size as f64;
+        length += 1;
+    }
+    length
+}
+
+/// The 62 base62 characters used by `random_string()` and
+/// `number_to_uid()`, in the order of their value when encoding.
+pub const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+abcdefghijklmnopqrstuvwxyz\
+0123456789";
+
+/// The 55 characters used by `human_random_string()`, which leaves out
+/// characters that are easily confused such as i, I, l, L, o, O and 0.
+pub const READABLE_CHARSET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ\
+abcdefghjkmnpqrstuvwxyz\
+123456789";
+
+/// The 10 digits used by `random_number()`.
+pub const NUMSET: &[u8] = b"0123456789";
+
+/// Lower case letters and numbers.
+pub const LOWERCASE_CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz\
+0123456789";
+
+/// Upper case letters and numbers.
+pub const UPPERCASE_CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+0123456789";
+
+/// Letters of a single case and numbers, so that no two UID's differ
+/// only by case. Use with backends that fold case, where `a8K` and
+/// `A8k` would otherwise collide.
+pub const CASE_INSENSITIVE_CHARSET: &[u8] = LOWERCASE_CHARSET;
+
+const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+abcdefghijklmnopqrstuvwxyz";
+
+const CONSONANTS: &[u8] = b"bcdfghjklmnprstvwz";
+
+const VOWELS: &[u8] = b"aeiou";
+
+const BASE58_CHARSET: &[u8] = b"123456789\
+ABCDEFGHJKLMNPQRSTUVWXYZ\
+abcdefghijkmnopqrstuvwxyz";
+
+const CROCKFORD_CHARSET: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+const SORTABLE_CHARSET: &[u8] = b"0123456789\
+ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+abcdefghijklmnopqrstuvwxyz";
+
+// Number of characters holding the time in a `ulid_string()`,
+// enough for milliseconds until the year 8800.
+const ULID_TIME_WIDTH: usize = 8;
+
+// Number of random characters in a `ulid_string()`.
+const ULID_RANDOM_LENGTH: usize = 12;
+
+// Number of base62 characters needed to represent `usize::MAX`.
+const SORTABLE_WIDTH: usize = {
+    let mut n = usize::MAX;
+    let mut width = 1;
+    while n >= 62 {
+        n /= 62;
+        width += 1;
+    }
+    width
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::base58_to_number;
+    use crate::base62_to_bytes;
+    use crate::bytes_to_base62;
+    use crate::crockford_to_number;
+    use crate::deterministic_uid;
+    use crate::deterministic_uid_in;
+    use crate::entropy_bits;
+    use crate::grouped_string;
+    use crate::human_random_string;
+    use crate::human_random_string_excluding;
+    use crate::human_uid_to_number;
+    use crate::is_valid_human_uid;
+    use crate::is_valid_uid;
+    use crate::keyspace_size;
+    use crate::keyspace_size_base62;
+    use crate::now_millis;
+    use crate::number_to_base58;
+    use crate::number_to_crockford;
+    use crate::number_to_human_uid;
+    use crate::number_to_radix;
+    use crate::number_to_uid;
+    use crate::number_to_uid_checked;
+    use crate::number_to_uid_fixed;
+    use crate::number_to_uid_padded;
+    use crate::number_to_uid_signed;
+    use crate::number_to_uid_sortable;
+    use crate::number_to_uid_u128;
+    use crate::number_to_uid_with_alphabet;
+    use crate::pronounceable_string;
+    use crate::radix_to_number;
+    use crate::random_bytes;
+    use crate::random_bytes_into;
+    use crate::random_lower_string;
+    use crate::random_max_size;
+    use crate::random_number;
+    use crate::random_string;
+    use crate::random_string_bits;
+    use crate::random_string_into;
+    use crate::random_string_lower;
+    use crate::random_string_no_repeats;
+    use crate::random_string_no_repeats_with_charset;
+    use crate::random_string_upper;
+    use crate::random_string_with_charset;
+    use crate::random_upper_string;
+    use crate::random_weighted_string;
+    use crate::recommended_length;
+    use crate::templated_string;
+    use crate::uid_digits;
+    use crate::uid_length_for;
+    use crate::uid_to_number;
+    use crate::uid_to_number_bounded;
+    use crate::uid_to_number_checked;
+    use crate::uid_to_number_ignore_case;
+    use crate::uid_to_number_lenient;
+    use crate::uid_to_number_signed;
+    use crate::uid_to_number_sortable;
+    use crate::uid_to_number_u128;
+    use crate::uid_with_alphabet_to_number;
+    use crate::ulid_string;
+    use crate::uuid_v4_string;
+    use crate::AlreadyExists;
+    use crate::Rng;
+    use crate::UidError;
+    use crate::UidFormat;
+    use crate::UidStore;
+
+    #[test]
+    fn test_number_to_uid() {
+        assert_eq!(number_to_uid(0), "A");
+        assert_eq!(number_to_uid(1), "B");
+        assert_eq!(number_to_uid(52), "0");
+        assert_eq!(number_to_uid(9902), "sjC");
+        assert_eq!(uid_to_number("A"), Some(0));
+        assert_eq!(uid_to_number("B"), Some(1));
+        assert_eq!(uid_to_number("0"), Some(52));
+        assert_eq!(uid_to_number("sjC"), Some(9902));
+        assert_eq!(uid_to_number(&number_to_uid(94029)), Some(94029));
+        assert_eq!(uid_to_number(&number_to_uid(2294029)), Some(2294029));
         assert_eq!(uid_to_number(&number_to_uid(43494029)), Some(43494029));
         assert_eq!(
             uid_to_number(&number_to_uid(uid_to_number("mRO").expect("ok"))).expect("ok"),
             uid_to_number("mRO").expect("ok")
         );
+        assert_eq!(uid_to_number(&number_to_uid(usize::MAX)), Some(usize::MAX));
+        assert_eq!(uid_to_number(&"z".repeat(15)), None);
+        assert_eq!(uid_to_number(""), None);
+        assert_eq!(uid_to_number_u128(""), None);
+        assert_eq!(radix_to_number("", 16), None);
+        assert_eq!(number_to_uid(0), "A");
+        assert_eq!(number_to_uid_u128(0), "A");
+        assert_eq!(number_to_radix(0, 2), "A");
+
+        assert_eq!(number_to_uid_padded(0, 4), Ok("AAAA".to_string()));
+        assert_eq!(number_to_uid_padded(9902, 5), Ok("sjCAA".to_string()));
+        assert_eq!(number_to_uid_padded(9902, 3), Ok("sjC".to_string()));
+        assert_eq!(number_to_uid_padded(9902, 2), Err(UidError::Overflow));
+        for n in [0, 1, 61, 62, 9902, 43494029] {
+            let uid = number_to_uid_padded(n, 8).expect("ok");
+            assert_eq!(uid.len(), 8);
+            assert_eq!(uid_to_number(&uid), Some(n));
+        }
+    }
+
+    #[test]
+    fn test_uid_to_number_lenient() {
+        assert_eq!(uid_to_number_lenient(" \tB9\r\n"), uid_to_number("B9"));
+        assert_eq!(uid_to_number_lenient("B9"), uid_to_number("B9"));
+        assert_eq!(uid_to_number_lenient("b9"), uid_to_number("b9"));
+        assert_eq!(uid_to_number_lenient("B 9"), None);
+        assert_eq!(uid_to_number_lenient("  "), None);
+
+        assert_eq!(uid_to_number_ignore_case(" abc "), uid_to_number("ABC"));
+        assert_eq!(uid_to_number_ignore_case("aBc"), uid_to_number("ABC"));
+        assert_eq!(uid_to_number_ignore_case("A09"), uid_to_number("A09"));
+        assert_eq!(uid_to_number_ignore_case("a-b"), None);
+    }
+
+    #[test]
+    fn test_uid_to_number_bounded() {
+        let max = u16::MAX as usize;
+        assert_eq!(uid_to_number_bounded("A", max), Some(0));
+        assert_eq!(uid_to_number_bounded(&number_to_uid(max), max), Some(max));
+        assert_eq!(uid_to_number_bounded(&number_to_uid(max + 1), max), None);
+        assert_eq!(uid_to_number_bounded("B", 0), None);
+        assert_eq!(uid_to_number_bounded("", max), None);
+        assert_eq!(uid_to_number_bounded("A-", max), None);
+        assert_eq!(uid_to_number_bounded(&"9".repeat(20), usize::MAX), None);
+        let mut u = UidStore::new();
+        for _ in 0..100 {
+            assert!(uid_to_number_bounded(u.next_u16(), max).is_some());
+        }
+    }
+
+    #[test]
+    fn test_next_bounded() {
+        let mut u = UidStore::new();
+        for _ in 0..200 {
+            assert!(uid_to_number_bounded(u.next_bounded::<u8>(), 255).is_some());
+        }
+        assert_eq!(u.size(), 200);
+        for _ in 0..100 {
+            let n = uid_to_number(u.next_bounded::<u16>()).expect("ok");
+            assert!(n <= u16::MAX as usize);
+            let n = uid_to_number(u.next_bounded::<u32>()).expect("ok");
+            assert!(n <= u32::MAX as usize);
+            assert!(uid_to_number_u128(u.next_bounded::<u64>()).expect("ok") <= u64::MAX as u128);
+            assert!(uid_to_number_u128(u.next_bounded::<u128>()).is_some());
+        }
+
+        let mut a = UidStore::with_seed([1, 2, 3, 4]);
+        let mut b = UidStore::with_seed([1, 2, 3, 4]);
+        assert_eq!(a.next_u16(), b.next_bounded::<u16>());
+        assert_eq!(a.next_u64(), b.next_bounded::<u64>());
+        assert_eq!(a.next_u128(), b.next_bounded::<u128>());
+    }
+
+    #[test]
+    fn test_next_bounded_exhausted() {
+        let mut u = UidStore::new();
+        for _ in 0..=u8::MAX {
+            u.next_bounded::<u8>();
+        }
+        let result = std::panic::catch_unwind(move || u.next_bounded::<u8>().clone());
+        let message = *result.unwrap_err().downcast::<String>().expect("message");
+        assert!(message.contains("no unused uid available"));
+    }
+
+    #[test]
+    fn test_next_sequential_u16() {
+        let mut u = UidStore::new();
+        u.make_unique(&number_to_uid(1234));
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..65535 {
+            let n = uid_to_number(u.next_sequential_u16()).expect("ok");
+            assert!(n <= u16::MAX as usize && n != 1234);
+            assert!(seen.insert(n));
+        }
+        assert_eq!(u.size(), 65536);
+        assert_eq!(u.remaining_u16(), 0);
+        assert!(std::panic::catch_unwind(move || u.next_sequential_u16().clone()).is_err());
+
+        let mut a = UidStore::with_seed([1, 2, 3, 4]);
+        let mut b = UidStore::with_seed([1, 2, 3, 4]);
+        let first: Vec<String> = (0..100).map(|_| a.next_sequential_u16().clone()).collect();
+        let second: Vec<String> = (0..100).map(|_| b.next_sequential_u16().clone()).collect();
+        assert_eq!(first, second);
+        let numbers: Vec<usize> = first.iter().map(|id| uid_to_number(id).unwrap()).collect();
+        assert!(numbers.windows(2).any(|w| w[1] != w[0] + 1));
+    }
+
+    #[test]
+    fn test_next_free_u16_sequential() {
+        let mut u = UidStore::new();
+        u.make_unique(&number_to_uid(1));
+        assert_eq!(
+            u.next_free_u16_sequential().cloned(),
+            Some(number_to_uid(0))
+        );
+        assert_eq!(
+            u.next_free_u16_sequential().cloned(),
+            Some(number_to_uid(2))
+        );
+        for n in 3..65536 {
+            assert_eq!(
+                u.next_free_u16_sequential().map(|id| uid_to_number(id)),
+                Some(Some(n))
+            );
+        }
+        assert_eq!(u.size(), 65536);
+        assert_eq!(u.next_free_u16_sequential(), None);
+        assert!(u.remove(&number_to_uid(700)));
+        assert!(u.remove(&number_to_uid(5)));
+        assert_eq!(
+            u.next_free_u16_sequential().cloned(),
+            Some(number_to_uid(5))
+        );
+        assert_eq!(
+            u.next_free_u16_sequential().cloned(),
+            Some(number_to_uid(700))
+        );
+        assert_eq!(u.next_free_u16_sequential(), None);
+        u.clear();
+        assert_eq!(
+            u.next_free_u16_sequential().cloned(),
+            Some(number_to_uid(0))
+        );
+    }
+
+    #[test]
+    fn test_human_numbers() {
+        assert_eq!(number_to_human_uid(0), "A");
+        assert_eq!(number_to_human_uid(55), "AB");
+        for n in [0, 1, 54, 55, 9902, u16::MAX as usize, usize::MAX] {
+            let uid = number_to_human_uid(n);
+            assert!(crate::is_valid_human_uid(&uid));
+            assert_eq!(human_uid_to_number(&uid), Some(n));
+        }
+        assert_eq!(human_uid_to_number(""), None);
+        assert_eq!(human_uid_to_number("0"), None);
+        assert_eq!(human_uid_to_number(&"z".repeat(15)), None);
+
+        let mut u = UidStore::new();
+        for _ in 0..1000 {
+            let n = human_uid_to_number(u.next_human_u16()).unwrap();
+            assert!(n <= u16::MAX as usize);
+            let n = human_uid_to_number(u.next_human_u32()).unwrap();
+            assert!(n <= u32::MAX as usize);
+            assert!(human_uid_to_number(u.next_human_u64()).is_some());
+        }
+        assert_eq!(u.size(), 3000);
+    }
+
+    #[test]
+    fn test_bytes_to_base62() {
+        let vectors: [(&[u8], &str); 7] = [
+            (&[], ""),
+            (&[0], "A"),
+            (&[61], "9"),
+            (&[62], "BA"),
+            (&[1, 0], "EI"),
+            (&[0, 0, 5], "AAF"),
+            (&[0xff; 4], "EqpPMD"),
+        ];
+        for (bytes, s) in vectors {
+            assert_eq!(bytes_to_base62(bytes), s);
+            assert_eq!(base62_to_bytes(s).as_deref(), Some(bytes));
+        }
+        for length in [1, 15, 16, 17, 64] {
+            for _ in 0..100 {
+                let mut bytes = random_bytes(length);
+                bytes[0] = 0;
+                let s = bytes_to_base62(&bytes);
+                assert!(s.starts_with('A'));
+                assert_eq!(base62_to_bytes(&s), Some(bytes));
+            }
+        }
+        let reversed: String = number_to_uid(0xffff_ffff).chars().rev().collect();
+        assert_eq!(bytes_to_base62(&[0xff; 4]), reversed);
+        assert_eq!(base62_to_bytes("A-B"), None);
+        assert_eq!(base62_to_bytes("é"), None);
+    }
+
+    #[test]
+    fn test_base58() {
+        assert_eq!(crate::BASE58_CHARSET.len(), 58);
+        let vectors = [
+            (0, "1"),
+            (1, "2"),
+            (57, "z"),
+            (58, "21"),
+            (255, "5Q"),
+            (3364, "211"),
+            (0xffff_ffff, "7YXq9G"),
+        ];
+        for (n, s) in vectors {
+            assert_eq!(number_to_base58(n), s);
+            assert_eq!(base58_to_number(s), Some(n));
+        }
+        assert_eq!(
+            base58_to_number(&number_to_base58(usize::MAX)),
+            Some(usize::MAX)
+        );
+        assert_eq!(base58_to_number(""), None);
+        assert_eq!(base58_to_number("0"), None);
+        assert_eq!(base58_to_number("O"), None);
+        assert_eq!(base58_to_number("I"), None);
+        assert_eq!(base58_to_number("l"), None);
+        assert_eq!(base58_to_number(&"z".repeat(12)), None);
+    }
+
+    #[test]
+    fn test_crockford() {
+        assert_eq!(number_to_crockford(0), "0");
+        assert_eq!(number_to_crockford(31), "Z");
+        assert_eq!(number_to_crockford(32), "10");
+        for n in [0, 1, 31, 32, 1234, 99_999, u32::MAX as usize, usize::MAX] {
+            let uid = number_to_crockford(n);
+            assert!(!uid.contains(['I', 'L', 'O', 'U']));
+            assert_eq!(crockford_to_number(&uid), Some(n));
+            assert_eq!(crockford_to_number(&uid.to_lowercase()), Some(n));
+        }
+        assert_eq!(crockford_to_number("1O"), Some(32));
+        assert_eq!(crockford_to_number("io"), Some(32));
+        assert_eq!(crockford_to_number("L0"), Some(32));
+        assert_eq!(crockford_to_number("16-j"), Some(1234));
+        assert_eq!(crockford_to_number(""), None);
+        assert_eq!(crockford_to_number("-"), None);
+        assert_eq!(crockford_to_number("U"), None);
+        assert_eq!(crockford_to_number("A*"), None);
+        assert_eq!(crockford_to_number(&"Z".repeat(14)), None);
+    }
+
+    #[test]
+    fn test_charsets() {
+        assert_eq!(crate::CHARSET.len(), 62);
+        assert_eq!(crate::READABLE_CHARSET.len(), 55);
+        assert_eq!(crate::NUMSET.len(), 10);
+        assert!(crate::READABLE_CHARSET
+            .iter()
+            .all(|c| crate::CHARSET.contains(c)));
+        assert!(random_number(20)
+            .bytes()
+            .all(|c| crate::NUMSET.contains(&c)));
+        assert_eq!(entropy_bits(1, crate::CHARSET.len()), 62f64.log2());
+    }
+
+    #[test]
+    fn test_is_valid_uid() {
+        assert!(is_valid_uid("sjC"));
+        assert!(is_valid_uid(&random_string(50)));
+        assert!(is_valid_uid(&"z".repeat(15)));
+        assert!(!is_valid_uid(""));
+        assert!(!is_valid_uid("ab-c"));
+        assert!(!is_valid_uid("é"));
+        assert!(is_valid_human_uid(&human_random_string(50)));
+        assert!(!is_valid_human_uid("abc0"));
+        assert!(!is_valid_human_uid(""));
+    }
+
+    #[test]
+    fn test_number_to_radix() {
+        assert_eq!(number_to_radix(0, 16), "A");
+        assert_eq!(number_to_radix(255, 16), "PP");
+        assert_eq!(number_to_radix(256, 16), "AAB");
+        assert_eq!(number_to_radix(5, 2), "BAB");
+        assert_eq!(number_to_radix(9902, 62), number_to_uid(9902));
+        for radix in [2, 10, 16, 36, 62] {
+            for n in [0, 1, 35, 36, 9902, usize::MAX] {
+                assert_eq!(radix_to_number(&number_to_radix(n, radix), radix), Some(n));
+            }
+        }
+        assert_eq!(radix_to_number("Q", 16), None);
+        assert_eq!(radix_to_number("A", 1), None);
+        assert_eq!(radix_to_number("A", 63), None);
+        assert_eq!(radix_to_number(&"B".repeat(65), 2), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_number_to_radix_invalid() {
+        number_to_radix(1, 63);
+    }
+
+    #[test]
+    fn test_number_to_uid_with_alphabet() {
+        let base62 = crate::CHARSET;
+        for n in [0, 1, 61, 62, 9902, usize::MAX] {
+            assert_eq!(number_to_uid_with_alphabet(n, base62), number_to_uid(n));
+        }
+        let alphabet = b"~0123456789";
+        assert_eq!(number_to_uid_with_alphabet(0, alphabet), "~");
+        assert_eq!(number_to_uid_with_alphabet(11, alphabet), "~0");
+        for n in [0, 1, 9, 10, 1000, usize::MAX] {
+            let uid = number_to_uid_with_alphabet(n, alphabet);
+            assert_eq!(uid_with_alphabet_to_number(&uid, alphabet), Some(n));
+        }
+        assert_eq!(uid_with_alphabet_to_number("", alphabet), None);
+        assert_eq!(uid_with_alphabet_to_number("A", alphabet), None);
+        assert_eq!(uid_with_alphabet_to_number("~", b"~"), None);
+        assert_eq!(uid_with_alphabet_to_number("a", b"aab"), None);
+        assert_eq!(uid_with_alphabet_to_number("1", "1é".as_bytes()), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_number_to_uid_with_alphabet_invalid() {
+        number_to_uid_with_alphabet(1, b"xx");
+    }
+
+    #[test]
+    fn test_uid_digits() {
+        assert_eq!(uid_digits("A"), Some(vec![0]));
+        assert_eq!(uid_digits("9"), Some(vec![61]));
+        assert_eq!(uid_digits("sjC"), Some(vec![2, 35, 44]));
+        assert_eq!(2 * 62 * 62 + 35 * 62 + 44, 9902);
+        for n in [0, 61, 62, 9902, usize::MAX] {
+            let digits = uid_digits(&number_to_uid(n)).expect("ok");
+            let value = digits
+                .iter()
+                .fold(0u128, |value, d| value * 62 + *d as u128);
+            assert_eq!(value, n as u128);
+        }
+        assert_eq!(uid_digits(""), None);
+        assert_eq!(uid_digits("A-"), None);
+    }
+
+    #[test]
+    fn test_uid_length_for() {
+        assert_eq!(uid_length_for(0), 1);
+        assert_eq!(uid_length_for(61), 1);
+        assert_eq!(uid_length_for(62), 2);
+        assert_eq!(uid_length_for(3843), 2);
+        assert_eq!(uid_length_for(3844), 3);
+        for n in [1, 9902, u16::MAX as usize, u32::MAX as usize, usize::MAX] {
+            assert_eq!(uid_length_for(n), number_to_uid(n).len());
+        }
+    }
+
+    #[test]
+    fn test_number_to_uid_fixed() {
+        let max = u16::MAX as usize;
+        assert_eq!(number_to_uid_fixed(0, max), "AAA");
+        assert_eq!(number_to_uid_fixed(max, max), number_to_uid(max));
+        for n in [0, 1, 61, 62, 3843, 3844, max] {
+            let uid = number_to_uid_fixed(n, max);
+            assert_eq!(uid.len(), 3);
+            assert_eq!(uid_to_number(&uid), Some(n));
+        }
+        assert_eq!(number_to_uid_fixed(0, 0), "A");
+        assert_eq!(
+            number_to_uid_fixed(5, usize::MAX).len(),
+            number_to_uid(usize::MAX).len()
+        );
+    }
+
+    #[test]
+    fn test_number_to_uid_checked() {
+        for n in [0, 1, 61, 62, 9902, 43494029, usize::MAX] {
+            let uid = number_to_uid_checked(n);
+            assert_eq!(uid.len(), number_to_uid(n).len() + 1);
+            assert!(uid.starts_with(&number_to_uid(n)));
+            assert_eq!(uid_to_number_checked(&uid), Some(n));
+        }
+        let uid = number_to_uid_checked(43494029);
+        for i in 0..uid.len() {
+            for c in crate::CHARSET.iter().map(|c| *c as char) {
+                let mut typo: Vec<char> = uid.chars().collect();
+                if typo[i] == c {
+                    continue;
+                }
+                typo[i] = c;
+                let typo: String = typo.into_iter().collect();
+                assert_eq!(uid_to_number_checked(&typo), None, "{}", typo);
+            }
+        }
+        let mut swapped: Vec<char> = uid.chars().collect();
+        swapped.swap(0, 1);
+        let swapped: String = swapped.into_iter().collect();
+        assert_eq!(uid_to_number_checked(&swapped), None);
+        assert_eq!(uid_to_number_checked(""), None);
+        assert_eq!(uid_to_number_checked("A"), None);
+        assert_eq!(uid_to_number_checked("A-"), None);
+    }
+
+    #[test]
+    fn test_number_to_uid_u128() {
+        assert_eq!(number_to_uid_u128(0), "A");
+        assert_eq!(number_to_uid_u128(9902), "sjC");
+        assert_eq!(
+            number_to_uid_u128(u64::MAX as u128),
+            number_to_uid(u64::MAX as usize)
+        );
+        for n in [0, 1, 61, 62, u64::MAX as u128 + 1, u128::MAX - 1, u128::MAX] {
+            assert_eq!(uid_to_number_u128(&number_to_uid_u128(n)), Some(n));
+        }
+        assert_eq!(uid_to_number_u128("-"), None);
+        assert_eq!(uid_to_number_u128(&"9".repeat(23)), None);
+
+        let mut u = UidStore::new();
+        for _ in 0..1000 {
+            let id = u.next_u128().clone();
+            assert!(uid_to_number_u128(&id).is_some());
+            assert!(u.contains(&id));
+        }
+        assert_eq!(u.size(), 1000);
+    }
+
+    #[test]
+    fn test_number_to_uid_signed() {
+        assert_eq!(number_to_uid_signed(0), "A");
+        assert_eq!(number_to_uid_signed(9902), "sjC");
+        assert_eq!(number_to_uid_signed(-9902), "-sjC");
+        for n in [0, 1, -1, 61, -62, 9902, i64::MAX, i64::MIN, i64::MIN + 1] {
+            assert_eq!(uid_to_number_signed(&number_to_uid_signed(n)), Some(n));
+        }
+        assert_eq!(uid_to_number_signed("-A"), None);
+        assert_eq!(uid_to_number_signed("-"), None);
+        assert_eq!(uid_to_number_signed("--B"), None);
+        assert_eq!(
+            uid_to_number_signed(&number_to_uid(u64::MAX as usize)),
+            None
+        );
+        let below_min = number_to_uid_u128(i64::MIN.unsigned_abs() as u128 + 1);
+        assert_eq!(uid_to_number_signed(&format!("-{}", below_min)), None);
+    }
+
+    #[test]
+    fn test_number_to_uid_sortable() {
+        assert_eq!(number_to_uid_sortable(0), "00000000000");
+        assert_eq!(number_to_uid_sortable(61), "0000000000z");
+        assert_eq!(number_to_uid_sortable(62), "00000000010");
+        assert!(number_to_uid_sortable(61) < number_to_uid_sortable(62));
+        for n in [
+            0,
+            1,
+            9,
+            10,
+            61,
+            62,
+            9902,
+            43494029,
+            usize::MAX - 1,
+            usize::MAX,
+        ] {
+            assert_eq!(uid_to_number_sortable(&number_to_uid_sortable(n)), Some(n));
+        }
+        let mut previous = number_to_uid_sortable(0);
+        for n in (1..1_000_000).step_by(997) {
+            let next = number_to_uid_sortable(n);
+            assert!(previous < next, "{} >= {}", previous, next);
+            previous = next;
+        }
+        assert!(number_to_uid_sortable(usize::MAX - 1) < number_to_uid_sortable(usize::MAX));
+        assert_eq!(uid_to_number_sortable("0"), None);
+        assert_eq!(uid_to_number_sortable("0000000000-"), None);
+        assert_eq!(uid_to_number_sortable("zzzzzzzzzzz"), None);
+    }
+
+    #[test]
+    fn test_ulid() {
+        let first = ulid_string();
+        assert_eq!(first.len(), 20);
+        assert!(crate::is_valid_uid(&first));
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let mut u = UidStore::new();
+        let second = u.next_ulid().clone();
+        assert!(first < second, "{} >= {}", first, second);
+        assert!(u.contains(&second));
+
+        let mut rng = crate::random::Source::Global;
+        let a = crate::generate_ulid(61, &mut rng);
+        let b = crate::generate_ulid(61, &mut rng);
+        assert_eq!(a[..8], b[..8]);
+        assert_ne!(a, b);
+        let c = crate::generate_ulid(62, &mut rng);
+        assert!(a < c && b < c);
+        assert!(c < crate::generate_ulid(now_millis(), &mut rng));
+    }
+
+    #[test]
+    fn test_uuid_v4() {
+        let mut u = UidStore::new();
+        for _ in 0..1_000 {
+            for id in [uuid_v4_string(), u.next_uuid().clone()] {
+                assert_eq!(id.len(), 36);
+                let groups: Vec<&str> = id.split('-').collect();
+                let lengths: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+                assert_eq!(lengths, vec![8, 4, 4, 4, 12]);
+                assert!(id
+                    .chars()
+                    .all(|c| c == '-' || c.is_ascii_digit() || ('a'..='f').contains(&c)));
+                assert!(groups[2].starts_with('4'));
+                assert!(groups[3].starts_with(['8', '9', 'a', 'b']));
+            }
+        }
+        assert_eq!(u.size(), 1_000);
+        assert_ne!(uuid_v4_string(), uuid_v4_string());
     }
 
     #[test]
     fn test_random_max_size() {
+        assert_eq!(random_max_size(0), "A");
+        assert!((0..1000).any(|_| random_max_size(1) == "B"));
+        assert!((0..1000).all(|_| uid_to_number(&random_max_size(1)) <= Some(1)));
         let mut u = UidStore::new();
-        for _ in [0..100000] {
+        for _ in 0..10000 {
             assert!(uid_to_number(u.next_u16()).unwrap() <= u16::MAX.into());
             assert!(uid_to_number(u.next_u32()).unwrap() <= u32::MAX.try_into().unwrap());
             assert!(uid_to_number(u.next_u64()).unwrap() <= u64::MAX.try_into().unwrap());
         }
         let mut u = UidStore::new();
-        for _ in [0..50000] {
+        for _ in 0..50000 {
             assert!(uid_to_number(u.next_u16()).unwrap() <= u16::MAX.into());
         }
         let mut u = UidStore::new();
         let _ = u.make_unique_u16("BBB");
-        for _ in [0..50000] {
+        for _ in 0..50000 {
             let v = u.make_unique_u16("BBB");
-            assert!(!v.is_none());
-            let v = uid_to_number(&v.unwrap()).unwrap();
+            assert!(v.is_some());
+            let v = uid_to_number(v.unwrap()).unwrap();
             assert!(v <= u16::MAX.into());
         }
+        let mut u = UidStore::new();
+        assert!(u.make_unique_u32("BBB").is_none());
+        for _ in 0..1000 {
+            let v = u.make_unique_u32("BBB").unwrap();
+            assert!(uid_to_number(v).unwrap() <= u32::MAX.try_into().unwrap());
+            let v = u.make_unique_u64("BBB").unwrap();
+            assert!(uid_to_number(v).is_some());
+        }
+        assert!(u.make_unique_u32("not-base62").is_none());
+        assert!(u.make_unique_u64("not base62 either").is_none());
+        assert!(u.contains("not-base62"));
     }
 
     #[test]
@@ -342,6 +3219,311 @@ mod tests {
         let id6 = human_random_string(5);
         assert_eq!(id5.len(), 5);
         assert!(id5 != id6);
+
+        let hex = b"0123456789ABCDEF";
+        let id7 = random_string_with_charset(32, hex).expect("ok");
+        assert_eq!(id7.len(), 32);
+        assert!(id7.bytes().all(|c| hex.contains(&c)));
+        assert_eq!(random_string_with_charset(4, b"x"), Ok("xxxx".to_string()));
+        assert_eq!(
+            random_string_with_charset(4, b""),
+            Err(UidError::EmptyCharset)
+        );
+        assert_eq!(
+            random_string_with_charset(4, b"ab\xe9"),
+            Err(UidError::NonAsciiCharset)
+        );
+
+        let id9 = random_weighted_string(1000, b"abc", &[0, 1, 3]).expect("ok");
+        assert_eq!(id9.len(), 1000);
+        assert!(!id9.contains('a'));
+        let c = id9.bytes().filter(|&c| c == b'c').count();
+        assert!(c > 650 && c < 850);
+        assert_eq!(
+            random_weighted_string(4, b"ab", &[0, 7]),
+            Ok("bbbb".to_string())
+        );
+        assert_eq!(
+            random_weighted_string(4, b"ab", &[1]),
+            Err(UidError::InvalidWeights)
+        );
+        assert_eq!(
+            random_weighted_string(4, b"ab", &[0, 0]),
+            Err(UidError::InvalidWeights)
+        );
+        assert_eq!(
+            random_weighted_string(4, b"", &[]),
+            Err(UidError::EmptyCharset)
+        );
+        assert_eq!(
+            random_weighted_string(4, b"a\xff", &[1, 1]),
+            Err(UidError::NonAsciiCharset)
+        );
+
+        let id8 = human_random_string_excluding(200, b"rnmIl1O0o").expect("ok");
+        assert_eq!(id8.len(), 200);
+        assert!(id8.bytes().all(|c| !b"rnmIl1O0o".contains(&c)));
+        assert_eq!(
+            human_random_string_excluding(3, &crate::CHARSET[1..]),
+            Ok("AAA".to_string())
+        );
+        assert_eq!(
+            human_random_string_excluding(3, crate::CHARSET),
+            Err(UidError::EmptyCharset)
+        );
+    }
+
+    #[cfg(feature = "secure")]
+    #[test]
+    fn test_random_secure() {
+        use crate::human_random_string_secure;
+        use crate::random_number_secure;
+        use crate::random_string_secure;
+
+        let id = random_string_secure(16);
+        assert_eq!(id.len(), 16);
+        assert!(uid_to_number_u128(&id).is_some());
+        assert_ne!(id, random_string_secure(16));
+        let id = random_number_secure(12);
+        assert!(id.bytes().all(|c| c.is_ascii_digit()));
+        assert_eq!(human_random_string_secure(8).len(), 8);
+    }
+
+    #[test]
+    fn test_single_case() {
+        let id = random_lower_string(100);
+        assert_eq!(id.len(), 100);
+        assert_eq!(id, id.to_lowercase());
+        assert!(id.bytes().all(|c| c.is_ascii_alphanumeric()));
+        let id = random_upper_string(100);
+        assert_eq!(id.len(), 100);
+        assert_eq!(id, id.to_uppercase());
+        assert!(id.bytes().all(|c| c.is_ascii_alphanumeric()));
+        assert_eq!(crate::CASE_INSENSITIVE_CHARSET.len(), 36);
+    }
+
+    #[test]
+    fn test_case_mapped() {
+        for _ in 0..1_000 {
+            let id = random_string_upper(12);
+            assert_eq!(id.len(), 12);
+            assert!(id.bytes().all(|c| crate::UPPERCASE_CHARSET.contains(&c)));
+            let id = random_string_lower(12);
+            assert_eq!(id.len(), 12);
+            assert!(id.bytes().all(|c| crate::LOWERCASE_CHARSET.contains(&c)));
+        }
+        let mut u = UidStore::new();
+        for _ in 0..36 {
+            let id = u.next_upper(1).clone();
+            assert!(crate::UPPERCASE_CHARSET.contains(&id.as_bytes()[0]));
+        }
+        assert_eq!(u.size(), 36);
+    }
+
+    #[test]
+    fn test_no_repeats() {
+        for _ in 0..1_000 {
+            let id = random_string_no_repeats(20);
+            assert_eq!(id.len(), 20);
+            assert!(id.as_bytes().windows(2).all(|w| w[0] != w[1]));
+        }
+        for _ in 0..100 {
+            let id = random_string_no_repeats_with_charset(9, b"ab").expect("ok");
+            assert!(id == "ababababa" || id == "babababab");
+        }
+        assert_eq!(
+            random_string_no_repeats_with_charset(3, b"a"),
+            Err(UidError::CharsetTooSmall)
+        );
+        assert_eq!(
+            random_string_no_repeats_with_charset(3, b""),
+            Err(UidError::EmptyCharset)
+        );
+        assert_eq!(
+            random_string_no_repeats_with_charset(3, b"a\x80"),
+            Err(UidError::NonAsciiCharset)
+        );
+
+        let mut u = UidStore::new();
+        for _ in 0..62 * 61 {
+            let id = u.next_no_repeats(2).clone();
+            assert_ne!(id.as_bytes()[0], id.as_bytes()[1]);
+        }
+        assert_eq!(u.size(), 62 * 61);
+    }
+
+    #[test]
+    fn test_random_string_into() {
+        let mut buf = [0u8; 16];
+        random_string_into(&mut buf, crate::CHARSET);
+        assert!(buf.iter().all(|c| crate::CHARSET.contains(c)));
+        let first = buf;
+        random_string_into(&mut buf, crate::CHARSET);
+        assert_ne!(first, buf);
+        random_string_into(&mut buf[..4], b"x");
+        assert_eq!(&buf[..4], b"xxxx");
+    }
+
+    #[test]
+    fn test_random_bytes() {
+        assert!(random_bytes(0).is_empty());
+        for n in [1, 7, 8, 9, 64, 1000] {
+            assert_eq!(random_bytes(n).len(), n);
+        }
+        assert_ne!(random_bytes(32), random_bytes(32));
+        let mut buf = [0u8; 13];
+        random_bytes_into(&mut buf);
+        assert_ne!(buf, [0u8; 13]);
+        let first = buf;
+        random_bytes_into(&mut buf);
+        assert_ne!(first, buf);
+    }
+
+    #[test]
+    fn test_random_threads() {
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                std::thread::spawn(|| (0..20_000).map(|_| random_string(12)).collect::<Vec<_>>())
+            })
+            .collect();
+        let mut all = std::collections::HashSet::new();
+        for handle in handles {
+            for id in handle.join().unwrap() {
+                assert!(all.insert(id), "threads produced the same uid");
+            }
+        }
+        assert_eq!(all.len(), 160_000);
+    }
+
+    #[test]
+    fn test_grouped() {
+        let id = grouped_string(12, 4, '-');
+        assert_eq!(id.len(), 14);
+        let groups: Vec<&str> = id.split('-').collect();
+        assert_eq!(groups.len(), 3);
+        assert!(groups.iter().all(|g| g.len() == 4));
+
+        let id = grouped_string(10, 4, ' ');
+        let groups: Vec<usize> = id.split(' ').map(|g| g.len()).collect();
+        assert_eq!(groups, vec![4, 4, 2]);
+        assert_eq!(grouped_string(3, 4, '-').len(), 3);
+        assert_eq!(grouped_string(0, 4, '-'), "");
+
+        let mut u = UidStore::new();
+        let id = u.next_grouped(8, 4, '-').clone();
+        assert_eq!(id.len(), 9);
+        assert!(u.contains(&id));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_grouped_separator() {
+        grouped_string(8, 4, 'x');
+    }
+
+    #[test]
+    fn test_deterministic_uid() {
+        let uid = deterministic_uid("someone@example.com", 12);
+        assert_eq!(uid, "vUeROKW4vTG0");
+        assert!(is_valid_uid(&uid));
+        assert_eq!(uid, deterministic_uid("someone@example.com", 12));
+        assert!(deterministic_uid("someone@example.com", 16).starts_with(&uid));
+        assert_ne!(uid, deterministic_uid("someone@example.org", 12));
+        assert_ne!(
+            uid,
+            deterministic_uid_in("users", "someone@example.com", 12)
+        );
+        assert_eq!(
+            deterministic_uid_in("users", "someone@example.com", 12),
+            deterministic_uid_in("users", "someone@example.com", 12)
+        );
+        assert_eq!(deterministic_uid("", 0), "");
+
+        let all: std::collections::HashSet<String> = (0..10_000)
+            .map(|n| deterministic_uid(&n.to_string(), 8))
+            .collect();
+        assert_eq!(all.len(), 10_000);
+    }
+
+    #[test]
+    fn test_templated() {
+        for _ in 0..1_000 {
+            let code = templated_string("AA-999*");
+            let bytes = code.as_bytes();
+            assert_eq!(bytes.len(), 7);
+            assert!(bytes[..2].iter().all(|c| c.is_ascii_alphabetic()));
+            assert_eq!(bytes[2], b'-');
+            assert!(bytes[3..6].iter().all(|c| c.is_ascii_digit()));
+            assert!(bytes[6].is_ascii_alphanumeric());
+        }
+        assert_eq!(templated_string(""), "");
+        assert_eq!(templated_string("x_y"), "x_y");
+        assert_eq!(templated_string("\\A\\9\\*\\\\"), "A9*\\");
+        assert_eq!(templated_string("\\"), "\\");
+        assert!(templated_string("é9").starts_with('é'));
+
+        let mut u = UidStore::new();
+        for _ in 0..10 {
+            assert!(u.next_templated("ord_9").starts_with("ord_"));
+        }
+        assert_eq!(u.size(), 10);
+        assert_eq!(u.next_templated("fixed"), "fixed");
+    }
+
+    #[test]
+    fn test_pronounceable() {
+        for length in [0, 1, 6, 7] {
+            let id = pronounceable_string(length);
+            assert_eq!(id.len(), length);
+            for (i, c) in id.bytes().enumerate() {
+                let charset = if i % 2 == 0 {
+                    crate::CONSONANTS
+                } else {
+                    crate::VOWELS
+                };
+                assert!(charset.contains(&c), "{}", id);
+            }
+        }
+        let mut u = UidStore::new();
+        for _ in 0..90 {
+            assert_eq!(u.next_pronounceable(2).len(), 2);
+        }
+        assert_eq!(u.size(), 90);
+    }
+
+    #[test]
+    fn test_entropy_bits() {
+        assert!((entropy_bits(8, 62) - 47.63).abs() < 0.01);
+        assert_eq!(entropy_bits(8, 16), 32.0);
+        assert_eq!(entropy_bits(0, 62), 0.0);
+        assert_eq!(entropy_bits(10, 1), 0.0);
+        let (id, bits) = random_string_bits(8);
+        assert_eq!(id.len(), 8);
+        assert_eq!(bits, entropy_bits(8, 62));
+    }
+
+    #[test]
+    fn test_keyspace_size() {
+        assert_eq!(keyspace_size(0, 62), Some(1));
+        assert_eq!(keyspace_size(3, 10), Some(1_000));
+        assert_eq!(keyspace_size(5, 0), Some(0));
+        assert_eq!(keyspace_size(128, 2), None);
+        assert_eq!(keyspace_size(127, 2), Some(1 << 127));
+        assert_eq!(keyspace_size(usize::MAX, 1), Some(1));
+        assert_eq!(keyspace_size_base62(1), Some(62));
+        assert_eq!(keyspace_size_base62(21), Some(62u128.pow(21)));
+        assert_eq!(keyspace_size_base62(22), None);
+    }
+
+    #[test]
+    fn test_recommended_length() {
+        assert_eq!(recommended_length(0, 62), 1);
+        assert_eq!(recommended_length(1, 62), 1);
+        assert_eq!(recommended_length(2, 62), 2);
+        assert_eq!(recommended_length(1_000, 62), 5);
+        assert_eq!(recommended_length(1_000_000, 62), 8);
+        assert_eq!(recommended_length(1_000_000, 10), 14);
+        assert_eq!(recommended_length(usize::MAX, 62), 23);
     }
 
     #[test]
@@ -376,5 +3558,839 @@ mod tests {
             assert_ne!(o.unwrap(), xo, "failed");
         };
         assert_eq!(u.size(), 4, "failed");
+
+        let o = u.make_unique_owned(xo.to_string());
+        assert!(o.as_ref().is_some_and(|o| o.len() == xo.len() && o != xo));
+        assert!(u.make_unique_owned("owned".to_string()).is_none());
+        assert!(u.contains("owned"));
+        assert_eq!(u.size(), 6, "failed");
+
+        assert!(u.remove("owned"));
+        assert!(u.remove(&o.unwrap()));
+        assert!(u.remove(xo));
+        assert!(!u.contains(xo));
+        assert!(!u.remove(xo));
+        assert_eq!(u.size(), 3, "failed");
+        assert!(u.make_unique(xo).is_none());
+
+        u.clear();
+        assert_eq!(u.size(), 0, "failed");
+        assert!(!u.contains(&id));
+    }
+
+    #[test]
+    fn test_next_many() {
+        let mut u = UidStore::new();
+        u.next(10);
+        let ids = u.next_many(100_000, 10);
+        assert_eq!(ids.len(), 100_000);
+        assert_eq!(u.size(), 100_001);
+        assert!(ids.iter().all(|id| id.len() == 10 && u.contains(id)));
+        let unique: std::collections::HashSet<&String> = ids.iter().collect();
+        assert_eq!(unique.len(), ids.len());
+        assert!(u.next_many(0, 10).is_empty());
+    }
+
+    #[test]
+    fn test_next_reuse() {
+        let mut u = UidStore::with_seed([1, 2, 3, 4]);
+        let mut v = UidStore::with_seed([1, 2, 3, 4]);
+        let mut buf = String::with_capacity(16);
+        let capacity = buf.capacity();
+        for _ in 0..100 {
+            u.next_reuse(16, &mut buf);
+            assert_eq!(&buf, v.next(16));
+            assert!(u.contains(&buf));
+        }
+        assert_eq!(buf.capacity(), capacity);
+        assert_eq!(u.size(), 100);
+
+        let mut u = UidStore::new();
+        u.reserve_prefix("A");
+        for _ in 0..61 {
+            u.next_reuse(1, &mut buf);
+            assert_ne!(buf, "A");
+        }
+        assert!(std::panic::catch_unwind(move || u.next_reuse(1, &mut buf)).is_err());
+    }
+
+    #[test]
+    fn test_next_with_attempts() {
+        let mut u = UidStore::new();
+        let (id, collisions) = u.next_with_attempts(12);
+        assert_eq!(id.len(), 12);
+        assert!(u.contains(&id));
+        assert_eq!(collisions, 0);
+
+        let mut total = 0;
+        for _ in 0..62 {
+            let (id, collisions) = u.next_with_attempts(1);
+            assert_eq!(id.len(), 1);
+            total += collisions;
+        }
+        assert_eq!(total, u.collision_count());
+        assert!(total > 0);
+    }
+
+    #[test]
+    fn test_next_variable() {
+        let mut u = UidStore::new();
+        let mut seen = [0; 13];
+        for _ in 0..10_000 {
+            let length = u.next_variable(4, 12).len();
+            assert!((4..=12).contains(&length));
+            seen[length] += 1;
+        }
+        assert!(seen[4..].iter().all(|count| *count > 0));
+        assert_eq!(u.size(), 10_000);
+        assert_eq!(u.next_variable(7, 7).len(), 7);
+        assert_eq!(u.next_variable(1, 1).len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "min must not be greater than max")]
+    fn test_next_variable_invalid() {
+        UidStore::new().next_variable(5, 4);
+    }
+
+    #[test]
+    fn test_next_for_population() {
+        let mut u = UidStore::new();
+        assert_eq!(u.next_for_population(1_000_000).len(), 8);
+        assert_eq!(u.next_for_population(10).len(), 8);
+        let mut u = UidStore::new();
+        for _ in 0..1_000 {
+            assert_eq!(u.next_for_population(1_000).len(), 5);
+        }
+        assert_eq!(u.next_for_population(usize::MAX).len(), 5);
+        assert_eq!(u.size(), 1_001);
+    }
+
+    #[test]
+    fn test_is_saturated() {
+        let mut u = UidStore::new();
+        assert!(!u.is_saturated(1));
+        u.next_many(55, 1);
+        assert!(!u.is_saturated(1));
+        u.next(1);
+        assert!(u.is_saturated(1));
+        assert!(!u.is_saturated(2));
+        assert!(!u.is_saturated(usize::MAX));
+    }
+
+    #[test]
+    fn test_would_likely_collide() {
+        let mut u = UidStore::new();
+        assert!(!u.would_likely_collide(0, 1));
+        assert!(!u.would_likely_collide(1, 1));
+        assert!(u.would_likely_collide(1_000, 4));
+        assert!(!u.would_likely_collide(1_000, 5));
+        assert!(!u.would_likely_collide(usize::MAX, usize::MAX));
+        u.next_many(1_000, 5);
+        assert!(!u.would_likely_collide(0, 5));
+        assert!(u.would_likely_collide(4_000, 5));
+        for count in [10, 1_000, 1_000_000] {
+            let length = recommended_length(count, 62);
+            assert!(!UidStore::new().would_likely_collide(count, length));
+            assert!(UidStore::new().would_likely_collide(count, length - 1));
+        }
+    }
+
+    #[test]
+    fn test_iter_unique() {
+        let mut u = UidStore::new();
+        let first = u.next(8).clone();
+        let ids: Vec<String> = u.iter_unique(8).take(20).collect();
+        assert_eq!(ids.len(), 20);
+        assert!(!ids.contains(&first));
+        assert_eq!(u.size(), 21);
+        assert!(ids.iter().all(|id| u.contains(id)));
+
+        let mut u = UidStore::new();
+        u.next(1);
+        assert_eq!(u.iter_unique(1).count(), 61);
+    }
+
+    #[test]
+    fn test_affix() {
+        let mut u = UidStore::new();
+        let id = u.next_with_prefix("usr_", 5).clone();
+        assert_eq!(id.len(), 9);
+        assert!(id.starts_with("usr_"));
+        assert!(u.contains(&id));
+        let id = u.next_with_suffix(".tmp", 5).clone();
+        assert_eq!(id.len(), 9);
+        assert!(id.ends_with(".tmp"));
+        assert!(u.contains(&id));
+
+        let mut a = UidStore::with_seed([9, 9, 9, 9]);
+        let mut b = UidStore::with_seed([9, 9, 9, 9]);
+        let usr = a.next_with_prefix("usr_", 5).clone();
+        let org = b.next_with_prefix("org_", 5).clone();
+        assert_eq!(usr[4..], org[4..]);
+        assert!(a.make_unique(&org).is_none());
+        assert_eq!(a.size(), 2);
+
+        let mut u = UidStore::new();
+        for _ in 0..62 {
+            u.next_with_prefix("p", 1);
+        }
+        assert_eq!(u.size(), 62);
+        assert!(u.next_with_prefix("q", 1).starts_with('q'));
+    }
+
+    #[test]
+    fn test_try_next_exhausted() {
+        let mut u = UidStore::new();
+        for _ in 0..62 {
+            assert_eq!(u.try_next(1).expect("ok").len(), 1);
+        }
+        assert_eq!(u.size(), 62);
+        assert_eq!(u.try_next(1), Err(UidError::Exhausted));
+        assert_eq!(u.size(), 62);
+        assert_eq!(u.total_generated(), 62);
+        assert!(u.collision_count() >= 62 * 32);
+        assert_eq!(u.try_next(2).expect("ok").len(), 2);
+    }
+
+    #[test]
+    fn test_next_clean() {
+        let mut u = UidStore::new();
+        let blocklist: std::collections::HashSet<String> =
+            ["a", "B", "xyz"].iter().map(|b| b.to_string()).collect();
+        for _ in 0..1_000 {
+            let id = u.next_clean(6, &blocklist).to_lowercase();
+            assert!(!id.contains('a') && !id.contains('b') && !id.contains("xyz"));
+        }
+        assert_eq!(u.size(), 1_000);
+        assert_eq!(u.next_clean(6, &Default::default()).len(), 6);
+
+        let everything: std::collections::HashSet<String> = crate::LOWERCASE_CHARSET
+            .iter()
+            .map(|c| (*c as char).to_string())
+            .collect();
+        assert_eq!(u.try_next_clean(6, &everything), Err(UidError::Rejected));
+        assert_eq!(u.size(), 1_001);
+        assert_eq!(
+            UidError::Rejected.to_string(),
+            "every generated uid was rejected"
+        );
+    }
+
+    #[test]
+    fn test_next_excluding() {
+        let mut u = UidStore::new();
+        let exclude: std::collections::HashSet<String> = crate::CHARSET[..60]
+            .iter()
+            .map(|c| (*c as char).to_string())
+            .collect();
+        let first = u.next_excluding(1, &exclude).clone();
+        let second = u.next_excluding(1, &exclude).clone();
+        let mut found = vec![first, second];
+        found.sort();
+        assert_eq!(found, ["8", "9"]);
+        assert_eq!(u.size(), 2);
+        assert!(exclude.iter().all(|id| !u.contains(id)));
+        let result = std::panic::catch_unwind(move || u.next_excluding(1, &exclude).clone());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_reserve_prefix() {
+        let mut u = UidStore::new();
+        assert!(u.reserved_prefixes().is_empty());
+        for c in "ABCDEFGHIJKLMNOPQRSTUVWXYZ".chars() {
+            u.reserve_prefix(&c.to_string());
+        }
+        u.reserve_prefix("a");
+        u.reserve_prefix("a");
+        assert_eq!(u.reserved_prefixes().len(), 27);
+        assert!(u.reserved_prefixes().contains("Q"));
+        for _ in 0..1_000 {
+            let id = u.next(4);
+            assert!(!id.starts_with(|c: char| c.is_ascii_uppercase() || c == 'a'));
+            let id = u.next_human(4);
+            assert!(!id.starts_with(|c: char| c.is_ascii_uppercase() || c == 'a'));
+        }
+        assert_eq!(u.make_unique("ABCD"), None);
+        assert_eq!(u.size(), 2_001);
+
+        u.reserve_prefix("");
+        assert_eq!(u.try_next(4), Err(UidError::Rejected));
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut u = UidStore::new();
+        assert_eq!(u.rotate("nope", 8), None);
+        let old = u.next(8).clone();
+        let new = u.rotate(&old, 10).expect("rotated").clone();
+        assert_eq!(new.len(), 10);
+        assert!(u.contains(&new));
+        assert!(!u.contains(&old));
+        assert_eq!(u.size(), 1);
+        assert_eq!(u.rotate(&old, 8), None);
+
+        // With one character UID's, the old value would often be drawn
+        // again if it were released first.
+        let mut u = UidStore::new();
+        for _ in 0..1_000 {
+            let old = u.next(1).clone();
+            let new = u.rotate(&old, 1).expect("rotated").clone();
+            assert_ne!(new, old);
+            u.remove(&new);
+        }
+    }
+
+    #[test]
+    fn test_try_claim() {
+        let mut u = UidStore::new();
+        assert!(u.try_claim("alice"));
+        assert!(!u.try_claim("alice"));
+        assert_eq!(u.size(), 1);
+        assert_eq!(u.total_generated(), 0);
+        assert!(u.try_claim("bob"));
+        let id = u.next(6).clone();
+        assert!(!u.try_claim(&id));
+        assert_eq!(u.size(), 3);
+    }
+
+    #[test]
+    fn test_insert_strict() {
+        let mut u = UidStore::new();
+        assert_eq!(u.insert_strict("abc"), Ok(()));
+        assert_eq!(u.insert_strict("abc"), Err(AlreadyExists));
+        assert_eq!(u.size(), 1);
+        assert_eq!(u.total_generated(), 0);
+        assert_eq!(AlreadyExists.to_string(), "uid is already in use");
+    }
+
+    #[test]
+    fn test_make_unique_batch() {
+        let mut u = UidStore::new();
+        u.make_unique("abc");
+        let replaced = u.make_unique_batch(&["abc", "def", "ghi", "def"]);
+        assert_eq!(replaced.len(), 2);
+        assert_eq!(replaced[0].0, "abc");
+        assert_eq!(replaced[1].0, "def");
+        for (original, replacement) in &replaced {
+            assert_ne!(original, replacement);
+            assert_eq!(replacement.len(), 3);
+            assert!(u.contains(replacement));
+        }
+        assert_eq!(u.size(), 5);
+        assert!(u.make_unique_batch(&[]).is_empty());
+        assert!(u.make_unique_batch(&["jkl"]).is_empty());
+    }
+
+    #[test]
+    fn test_max_retries() {
+        let mut u = UidStore::new();
+        assert_eq!(u.max_retries(), usize::MAX);
+        u.set_max_retries(0);
+        assert_eq!(u.max_retries(), 0);
+        for _ in 0..100 {
+            u.next(10);
+        }
+        for c in crate::CHARSET {
+            u.make_unique(&(*c as char).to_string());
+        }
+        assert_eq!(u.try_next(1), Err(UidError::RetryLimit));
+        assert!(std::panic::catch_unwind(move || u.next_human(1).clone()).is_err());
+
+        let mut u = UidStore::new();
+        u.set_max_retries(3);
+        for n in 0..=u16::MAX as usize {
+            u.make_unique(&number_to_uid(n));
+        }
+        let result = std::panic::catch_unwind(move || u.next_u16().clone());
+        let message = *result.unwrap_err().downcast::<String>().expect("message");
+        assert!(message.contains("retry limit reached"));
+    }
+
+    #[test]
+    fn test_next_human_exhausted() {
+        let mut u = UidStore::new();
+        for _ in 0..crate::READABLE_CHARSET.len() {
+            u.next_human(1);
+        }
+        let result = std::panic::catch_unwind(move || u.next_human(1).clone());
+        let message = *result.unwrap_err().downcast::<String>().expect("message");
+        assert!(message.contains("no unused uid available"));
+    }
+
+    #[test]
+    fn test_on_collision() {
+        use std::sync::{Arc, Mutex};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut u = UidStore::with_seed([1, 2, 3, 4]);
+        let log = Arc::clone(&seen);
+        u.on_collision(Box::new(move |id| log.lock().unwrap().push(id.to_string())));
+        u.next_many(62, 1);
+        let collisions = seen.lock().unwrap().clone();
+        assert_eq!(collisions.len(), u.collision_count());
+        assert!(!collisions.is_empty());
+        assert!(collisions.iter().all(|id| id.len() == 1 && u.contains(id)));
+
+        let mut clone = u.clone();
+        assert!(std::panic::catch_unwind(move || clone.next(1).clone()).is_err());
+        assert!(seen.lock().unwrap().len() > collisions.len());
+    }
+
+    #[test]
+    fn test_min_regen_length() {
+        let mut u = UidStore::new();
+        assert_eq!(u.min_regen_length(), 0);
+        u.make_unique("AB");
+        assert_eq!(u.make_unique("AB").expect("replaced").len(), 2);
+
+        let mut u = UidStore::new();
+        u.set_min_regen_length(6);
+        assert_eq!(u.min_regen_length(), 6);
+        let mut replaced = 0;
+        for _ in 0..20_000 {
+            let id = random_string(2);
+            match u.make_unique(&id) {
+                Some(new_id) => {
+                    assert_eq!(new_id.len(), 6);
+                    replaced += 1;
+                }
+                None => assert!(u.contains(&id)),
+            }
+        }
+        assert_eq!(u.size(), 20_000);
+        assert!(replaced >= 20_000 - 62 * 62);
+        assert_eq!(u.make_unique("ABCDEFGHIJ"), None);
+        assert_eq!(u.make_unique("ABCDEFGHIJ").expect("replaced").len(), 10);
+        u.make_unique("AB");
+        assert_eq!(
+            u.make_unique_owned("AB".to_string())
+                .expect("replaced")
+                .len(),
+            6
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut u = UidStore::new();
+        for _ in 0..100 {
+            u.next(8);
+        }
+        u.make_unique("0123456789");
+        let json = serde_json::to_string(&u).expect("ok");
+        assert!(json.starts_with('['));
+        let restored: UidStore = serde_json::from_str(&json).expect("ok");
+        assert_eq!(restored.size(), u.size());
+        assert!(restored.contains("0123456789"));
+
+        let restored: UidStore = serde_json::from_str(r#"["A", "B", "A"]"#).expect("ok");
+        assert_eq!(restored.size(), 2);
+        assert!(restored.contains("A"));
+    }
+
+    #[test]
+    fn test_clone_eq() {
+        let mut u = UidStore::default();
+        assert_eq!(u, UidStore::new());
+        u.next(8);
+        let snapshot = u.clone();
+        assert_eq!(u, snapshot);
+        u.next(8);
+        assert_ne!(u, snapshot);
+        assert_eq!(snapshot.size(), 1);
+        assert_eq!(u.size(), 2);
+
+        let mut a = UidStore::with_seed([5, 6, 7, 8]);
+        a.next(8);
+        let mut b = a.clone();
+        assert_eq!(a.next(8), b.next(8));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let existing = vec!["abc".to_string(), "def".to_string(), "abc".to_string()];
+        let mut u: UidStore = existing.into_iter().collect();
+        assert_eq!(u.size(), 2);
+        assert!(u.contains("abc"));
+        assert!(u.contains("def"));
+        u.extend(vec!["def".to_string(), "ghi".to_string()]);
+        assert_eq!(u.size(), 3);
+        assert!(u.contains("ghi"));
+    }
+
+    #[test]
+    fn test_from_strings_checked() {
+        let items = ["c", "a", "b", "a", "c", "a"].map(String::from);
+        let (u, repeated) = UidStore::from_strings_checked(items);
+        assert_eq!(u.size(), 3);
+        assert!(u.contains("a") && u.contains("b") && u.contains("c"));
+        assert_eq!(repeated, ["a", "c", "a"]);
+
+        let (u, repeated) = UidStore::from_strings_checked(Vec::new());
+        assert_eq!(u.size(), 0);
+        assert!(repeated.is_empty());
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut u = UidStore::with_capacity(1000);
+        assert!(u.capacity() >= 1000);
+        assert_eq!(u.size(), 0);
+        u.reserve(5000);
+        assert!(u.capacity() >= 5000);
+        u.next(8);
+        u.shrink_to_fit();
+        assert!(u.capacity() < 5000);
+        assert_eq!(u.size(), 1);
+    }
+
+    #[test]
+    fn test_estimated_bytes() {
+        let empty = UidStore::new().estimated_bytes();
+        let mut short = UidStore::new();
+        let mut long = UidStore::new();
+        for _ in 0..1000 {
+            short.next(8);
+            long.next(32);
+        }
+        assert!(short.estimated_bytes() >= 1000 * (8 + std::mem::size_of::<String>()));
+        assert!(long.estimated_bytes() >= short.estimated_bytes() + 1000 * 24);
+        short.next_many(1000, 8);
+        assert!(short.estimated_bytes() >= 2000 * (8 + std::mem::size_of::<String>()));
+        short.clear();
+        short.shrink_to_fit();
+        assert_eq!(short.estimated_bytes(), empty);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut u = UidStore::new();
+        for _ in 0..10 {
+            u.next_with_prefix("keep_", 6);
+            u.next_with_prefix("drop_", 6);
+        }
+        assert_eq!(u.size(), 20);
+        u.retain(|id| id.starts_with("keep_"));
+        assert_eq!(u.size(), 10);
+        u.retain(|_| false);
+        assert_eq!(u.size(), 0);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a: UidStore = vec!["a".to_string(), "b".to_string()].into_iter().collect();
+        let b: UidStore = vec!["c".to_string(), "d".to_string()].into_iter().collect();
+        let c: UidStore = vec!["b".to_string(), "e".to_string()].into_iter().collect();
+        assert!(a.is_disjoint(&b));
+        assert!(!a.is_disjoint(&c));
+        assert!(a.merge(&b).is_empty());
+        assert_eq!(a.size(), 4);
+        assert_eq!(a.merge(&c), vec!["b".to_string()]);
+        assert_eq!(a.size(), 5);
+        assert!(a.contains("e"));
+        assert_eq!(c.size(), 2);
+    }
+
+    #[test]
+    fn test_contains_any() {
+        let u: UidStore = vec!["a".to_string(), "b".to_string()].into_iter().collect();
+        assert_eq!(
+            u.contains_any(&["x", "b", "y", "a", "b"]),
+            vec!["b", "a", "b"]
+        );
+        assert!(u.contains_any(&["x", "y"]).is_empty());
+        assert!(u.contains_any(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_next_formatted() {
+        let format = UidFormat::new().length(5).prefix("ord_").suffix("!");
+        let mut u = UidStore::new();
+        let id = u.next_formatted(&format).clone();
+        assert_eq!(id.len(), 10);
+        assert!(id.starts_with("ord_") && id.ends_with('!'));
+        assert!(u.contains(&id));
+
+        let format = UidFormat::new().length(20).human(true);
+        let id = u.next_formatted(&format).clone();
+        assert!(id.bytes().all(|c| crate::READABLE_CHARSET.contains(&c)));
+        let format = UidFormat::new().human(true).human(false);
+        assert_eq!(format, UidFormat::new());
+        let format = UidFormat::new().charset(b"ab").human(false);
+        assert_eq!(format.charset, b"ab");
+
+        let format = UidFormat::new().length(1).charset(b"ab");
+        assert_eq!(u.next_formatted(&format).len(), 1);
+        assert_eq!(u.next_formatted(&format).len(), 1);
+        assert_eq!(u.try_next_formatted(&format), Err(UidError::Exhausted));
+
+        let format = UidFormat::default().charset(b"");
+        assert_eq!(u.try_next_formatted(&format), Err(UidError::EmptyCharset));
+        let format = UidFormat::default().charset("ab\u{e9}".as_bytes());
+        assert_eq!(
+            u.try_next_formatted(&format),
+            Err(UidError::NonAsciiCharset)
+        );
+        assert_eq!(UidFormat::default().length, 8);
+    }
+
+    #[test]
+    fn test_remaining() {
+        let mut u = UidStore::new();
+        assert_eq!(u.remaining_u16(), 65536);
+        assert_eq!(u.remaining_u32(), 1 << 32);
+        for n in 0..100 {
+            u.make_unique(&number_to_uid(n));
+        }
+        u.make_unique("not base62");
+        u.make_unique(&number_to_uid(70000));
+        u.make_unique(&number_to_uid_padded(9902, 5).unwrap());
+        u.make_unique("sjC");
+        assert_eq!(u.remaining_u16(), 65536 - 101);
+        assert_eq!(u.remaining_u32(), (1 << 32) - 102);
+
+        let mut u = UidStore::new();
+        for _ in 0..65536 {
+            u.next_u16();
+        }
+        assert_eq!(u.remaining_u16(), 0);
+        assert!(u.contains(&number_to_uid(u16::MAX as usize)));
+    }
+
+    #[test]
+    fn test_reserve_number_range() {
+        let mut u = UidStore::new();
+        u.reserve_number_range(1, 10_001);
+        assert_eq!(u.size(), 10_000);
+        assert!(!u.contains_number(0));
+        assert!(u.contains_number(1));
+        assert!(u.contains_number(10_000));
+        assert!(!u.contains_number(10_001));
+        u.reserve_number_range(5, 5);
+        u.reserve_number_range(9, 2);
+        assert_eq!(u.size(), 10_000);
+
+        let mut u = UidStore::new();
+        u.reserve_number_range(0, 65_000);
+        for _ in 0..536 {
+            assert!(uid_to_number(u.next_u16()).expect("ok") >= 65_000);
+        }
+        assert_eq!(u.remaining_u16(), 0);
+    }
+
+    #[test]
+    fn test_contains_number() {
+        let mut u = UidStore::new();
+        let id = u.next_u32().clone();
+        let n = uid_to_number(&id).unwrap();
+        assert!(u.contains_number(n));
+        assert!(u.remove_number(n));
+        assert!(!u.contains_number(n));
+        assert!(!u.remove_number(n));
+
+        u.make_unique(&number_to_uid_padded(9902, 6).unwrap());
+        assert!(u.contains_number(9902));
+        assert!(!u.contains("sjC"));
+        u.make_unique("sjC");
+        assert_eq!(u.size(), 2);
+        assert!(u.remove_number(9902));
+        assert_eq!(u.size(), 0);
+
+        u.make_unique("A");
+        assert!(u.contains_number(0));
+        assert!(!u.contains_number(1));
+    }
+
+    #[test]
+    fn test_peek_commit() {
+        let mut u = UidStore::new();
+        let candidate = u.peek(8);
+        assert_eq!(candidate.len(), 8);
+        assert!(!u.contains(&candidate));
+        assert_eq!(u.size(), 0);
+        assert!(u.commit(&candidate));
+        assert!(u.contains(&candidate));
+        assert!(!u.commit(&candidate));
+        assert_eq!(u.size(), 1);
+
+        let mut u = UidStore::new();
+        for _ in 0..61 {
+            u.next(1);
+        }
+        let last = u.peek(1);
+        assert!(!u.contains(&last));
+        assert!(u.commit(&last));
+        assert_eq!(u.try_next(1), Err(UidError::Exhausted));
+    }
+
+    #[test]
+    fn test_peek_reserved_prefix() {
+        let mut u = UidStore::new();
+        for c in "ABCDEFGHIJKLMNOPQRSTUVWXYZcdefghijklmnopqrstuvwxyz0123456789".chars() {
+            u.reserve_prefix(&c.to_string());
+        }
+        for _ in 0..1_000 {
+            let id = u.peek(1);
+            assert!(id == "a" || id == "b", "{}", id);
+        }
+        assert_eq!(u.size(), 0);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let mut u = UidStore::new();
+        assert_eq!(u.total_generated(), 0);
+        assert_eq!(u.collision_count(), 0);
+        u.make_unique("abc");
+        assert_eq!(u.total_generated(), 0);
+        u.make_unique("abc");
+        assert_eq!(u.total_generated(), 1);
+        for _ in 0..20 {
+            u.next_human(1);
+        }
+        assert_eq!(u.total_generated(), 21);
+        u.next_u16();
+        u.next_u128();
+        assert_eq!(u.total_generated(), 23);
+
+        let mut u = UidStore::new();
+        for _ in 0..62 {
+            u.next(1);
+        }
+        assert_eq!(u.total_generated(), 62);
+        assert!(u.collision_count() > 0);
+        u.clear();
+        assert_eq!(u.total_generated(), 62);
+        assert_eq!(u.issued_total(), 62);
+
+        let mut u = UidStore::new();
+        let id = u.peek(8);
+        assert_eq!(u.issued_total(), 0);
+        assert!(u.commit(&id));
+        assert!(!u.commit(&id));
+        assert_eq!(u.issued_total(), 1);
+        u.next_many(10, 8);
+        u.remove(&id);
+        u.clear();
+        assert_eq!(u.size(), 0);
+        assert_eq!(u.issued_total(), 11);
+    }
+
+    #[test]
+    fn test_into_iter_drain() {
+        let mut u = UidStore::new();
+        let ids = u.next_many(50, 6);
+        let mut listed: Vec<&String> = u.iter().collect();
+        listed.sort();
+        assert_eq!(listed.len(), 50);
+        assert!(listed.iter().all(|id| ids.contains(id)));
+        assert_eq!(u.size(), 50);
+        assert!(u.to_sorted_vec().iter().eq(listed.iter().copied()));
+        let mut drained: Vec<String> = u.drain().collect();
+        assert_eq!(u.size(), 0);
+        let mut sorted = ids.clone();
+        sorted.sort();
+        drained.sort();
+        assert_eq!(drained, sorted);
+
+        let u: UidStore = ids.clone().into_iter().collect();
+        let mut consumed: Vec<String> = u.into_iter().collect();
+        consumed.sort();
+        assert_eq!(consumed, sorted);
+    }
+
+    #[test]
+    fn test_with_rng() {
+        struct Script(Vec<u32>);
+
+        impl Rng for Script {
+            fn next_u64(&mut self) -> u64 {
+                self.next_u32() as u64
+            }
+
+            fn next_u32(&mut self) -> u32 {
+                self.0.remove(0)
+            }
+        }
+
+        let mut u = UidStore::with_rng(Script(vec![0, 1, 2, 0, 1, 2, 61, 62, 9]));
+        assert_eq!(u.next(3), "ABC");
+        assert_eq!(u.next(3), "9AJ");
+        assert_eq!(u.collision_count(), 1);
+        assert!(format!("{:?}", u).contains("Custom"));
+    }
+
+    #[test]
+    fn test_with_seed() {
+        let seed = [1, 2, 3, 4];
+        let mut a = UidStore::with_seed(seed);
+        let mut b = UidStore::with_seed(seed);
+        for _ in 0..100 {
+            assert_eq!(a.next(6), b.next(6));
+        }
+        assert_eq!(a.next_u32(), b.next_u32());
+
+        let mut c = UidStore::with_seed([4, 3, 2, 1]);
+        assert_ne!(UidStore::with_seed(seed).next(10), c.next(10));
+    }
+
+    #[test]
+    fn test_reseed() {
+        let mut a = UidStore::with_seed([1, 2, 3, 4]);
+        let first = a.next(8).clone();
+        a.next(8);
+        a.reseed([1, 2, 3, 4]);
+        assert!(a.contains(&first));
+        assert_eq!(a.size(), 2);
+        assert_ne!(a.next(8), &first);
+
+        let mut b = UidStore::new();
+        b.next(8);
+        b.reseed([5, 6, 7, 8]);
+        let mut c = UidStore::with_seed([5, 6, 7, 8]);
+        for _ in 0..10 {
+            assert_eq!(b.next(8), c.next(8));
+        }
+    }
+
+    #[test]
+    fn test_fork() {
+        for mut parent in [UidStore::new(), UidStore::with_seed([1, 2, 3, 4])] {
+            parent.reserve_prefix("admin");
+            let mut children: Vec<UidStore> = (0..5).map(|_| parent.fork()).collect();
+            let mut all = std::collections::HashSet::new();
+            for store in children.iter_mut().chain([&mut parent]) {
+                assert_eq!(store.size(), 0);
+                assert_eq!(store.reserved_prefixes().len(), 1);
+                for _ in 0..1_000 {
+                    assert!(all.insert(store.next(8).clone()));
+                }
+            }
+            assert_eq!(all.len(), 6_000);
+        }
+
+        let mut a = UidStore::with_seed([1, 2, 3, 4]);
+        let mut b = UidStore::with_seed([1, 2, 3, 4]);
+        assert_eq!(a.fork().next(8), b.fork().next(8));
+        assert_eq!(a.next(8), b.next(8));
+    }
+
+    #[test]
+    fn test_with_string_seed() {
+        let mut a = UidStore::with_string_seed("test_with_string_seed");
+        let mut b = UidStore::with_string_seed("test_with_string_seed");
+        for _ in 0..100 {
+            assert_eq!(a.next(6), b.next(6));
+        }
+        assert_eq!(a.next_human(8), b.next_human(8));
+        let mut c = UidStore::with_string_seed("another test");
+        assert_ne!(
+            UidStore::with_string_seed("test_with_string_seed").next(10),
+            c.next(10)
+        );
     }
 }
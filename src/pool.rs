@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+
+use crate::UidStore;
+
+/// UidPool keeps a queue of UID's generated ahead of time, so most
+/// calls to `take()` only remove a value from the front of the queue.
+/// Pooled UID's are already held by the underlying `UidStore`, so they
+/// are never generated again, even if they are never taken.
+///
+/// ```rust
+/// # use uid_store::*;
+/// let mut pool = UidPool::new(UidStore::new(), 8, 100);
+/// let id = pool.take().unwrap();
+/// assert_eq!(id.len(), 8);
+/// assert!(pool.store().contains(&id));
+/// ```
+#[derive(Debug, Clone)]
+pub struct UidPool {
+    store: UidStore,
+    pool: VecDeque<String>,
+    length: usize,
+    capacity: usize,
+    threshold: usize,
+}
+
+impl UidPool {
+    /// Create a pool of `capacity` UID's with a `length` number of
+    /// characters, generated by `store`. The pool is refilled once
+    /// fewer than a quarter of `capacity` UID's remain.
+    pub fn new(store: UidStore, length: usize, capacity: usize) -> UidPool {
+        let mut pool = UidPool {
+            store,
+            pool: VecDeque::with_capacity(capacity),
+            length,
+            capacity,
+            threshold: capacity / 4,
+        };
+        pool.refill();
+        pool
+    }
+
+    /// Refill the pool once fewer than `threshold` UID's remain.
+    pub fn with_threshold(mut self, threshold: usize) -> UidPool {
+        self.threshold = threshold.min(self.capacity);
+        self
+    }
+
+    /// Take the next UID from the pool, refilling it first if it has
+    /// fallen below the threshold. Returns None if the pool is empty
+    /// and every UID of this length is already in use.
+    pub fn take(&mut self) -> Option<String> {
+        if self.pool.len() < self.threshold || self.pool.is_empty() {
+            self.refill();
+        }
+        self.pool.pop_front()
+    }
+
+    /// Generate UID's until the pool holds `capacity` of them, or
+    /// every UID of this length is in use.
+    pub fn refill(&mut self) {
+        while self.pool.len() < self.capacity {
+            match self.store.try_next(self.length) {
+                Ok(id) => self.pool.push_back(id.clone()),
+                Err(_) => break,
+            }
+        }
+    }
+
+    /// Returns the number of UID's ready to be taken.
+    pub fn available(&self) -> usize {
+        self.pool.len()
+    }
+
+    /// Returns the underlying `UidStore`, which holds every UID taken
+    /// from the pool as well as those still waiting in it.
+    pub fn store(&self) -> &UidStore {
+        &self.store
+    }
+
+    /// Consume the pool, returning the underlying `UidStore`. UID's
+    /// still in the pool remain in use.
+    pub fn into_store(self) -> UidStore {
+        self.store
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::UidPool;
+    use crate::UidStore;
+
+    #[test]
+    fn test_take() {
+        let mut pool = UidPool::new(UidStore::new(), 6, 100);
+        assert_eq!(pool.available(), 100);
+        assert_eq!(pool.store().size(), 100);
+        let mut taken = HashSet::new();
+        for _ in 0..75 {
+            assert!(taken.insert(pool.take().expect("uid")));
+        }
+        assert_eq!(pool.available(), 25);
+        assert!(taken.insert(pool.take().expect("uid")));
+        assert_eq!(pool.available(), 24);
+        assert!(taken.insert(pool.take().expect("uid")));
+        assert_eq!(pool.available(), 99);
+        assert_eq!(pool.store().size(), 176);
+        assert!(taken
+            .iter()
+            .all(|id| id.len() == 6 && pool.store().contains(id)));
+    }
+
+    #[test]
+    fn test_threshold() {
+        let mut pool = UidPool::new(UidStore::new(), 6, 10).with_threshold(0);
+        for _ in 0..10 {
+            pool.take().expect("uid");
+        }
+        assert_eq!(pool.available(), 0);
+        pool.take().expect("uid");
+        assert_eq!(pool.available(), 9);
+    }
+
+    #[test]
+    fn test_exhausted() {
+        let mut pool = UidPool::new(UidStore::new(), 1, 100);
+        assert_eq!(pool.available(), 62);
+        for _ in 0..62 {
+            pool.take().expect("uid");
+        }
+        assert_eq!(pool.take(), None);
+        assert_eq!(pool.into_store().size(), 62);
+    }
+}
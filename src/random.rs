@@ -1,7 +1,47 @@
-use std::sync::Mutex;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::thread;
 use std::time::SystemTime;
 
+/// A source of random numbers that can drive a `UidStore`. This allows
+/// a deterministic implementation to be supplied in tests, so the exact
+/// UID values a store generates can be asserted.
+///
+/// ```rust
+/// # use uid_store::*;
+/// struct Counter(u32);
+///
+/// impl Rng for Counter {
+///     fn next_u64(&mut self) -> u64 {
+///         self.0 += 1;
+///         self.0 as u64
+///     }
+///
+///     fn next_u32(&mut self) -> u32 {
+///         self.0 += 1;
+///         self.0
+///     }
+/// }
+///
+/// let mut u = UidStore::with_rng(Counter(0));
+/// assert_eq!(u.next(3), "BCD");
+/// ```
+pub trait Rng {
+    /// Returns the next random `u64`.
+    fn next_u64(&mut self) -> u64;
+
+    /// Returns the next random `u32`, by default the
+    /// upper half of `next_u64()`.
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+}
+
 // Implement xoshiro256ss from https://en.wikipedia.org/wiki/Xorshift
+#[derive(Debug, Clone)]
 pub(crate) struct PseudoRandom {
     s: [u64; 4],
 }
@@ -13,19 +53,32 @@ impl PseudoRandom {
             Ok(t) => t.as_nanos(),
             Err(_) => 0,
         };
-        self.s = [
-            (now ^ 4690481050117892527) as u64,
-            ((now * 50000) ^ 13682126131931052725) as u64,
-            (now ^ 9639264971936262885) as u64,
-            (now ^ 6412797481073129502) as u64,
-        ];
+        // Threads seeded in the same nanosecond must not share a sequence.
+        let mut hasher = DefaultHasher::new();
+        thread::current().id().hash(&mut hasher);
+        self.seed_from(now, hasher.finish());
+    }
+
+    // Expand the time and thread into a full state using splitmix64.
+    // Consecutive splitmix64 outputs are distinct, so at most one state
+    // word can be zero and the all zero state can never be produced.
+    fn seed_from(&mut self, now: u128, thread: u64) {
+        let mut x = (now as u64) ^ ((now >> 64) as u64) ^ thread;
+        for s in self.s.iter_mut() {
+            *s = splitmix64(&mut x);
+        }
     }
 
     #[inline]
-    pub(crate) fn next_u64(&mut self) -> u64 {
-        if self.s[0] == 0 {
+    fn seed_if_zero(&mut self) {
+        if self.s == [0, 0, 0, 0] {
             self.seed();
         }
+    }
+
+    #[inline]
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.seed_if_zero();
         let next = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
         let v = self.s[1] << 17;
         self.s[2] ^= self.s[0];
@@ -42,32 +95,263 @@ impl PseudoRandom {
         (self.next_u64() >> 32) as u32
     }
 
-    /*
-    pub fn new_with_seed(seed: [u64; 4]) -> PseudoRandom {
+    /// An all zero seed can not drive xoshiro256, so it is
+    /// replaced with a time based seed on first use.
+    pub(crate) fn new_with_seed(seed: [u64; 4]) -> PseudoRandom {
         PseudoRandom { s: seed }
     }
 
+    /// Derive a seed from the bytes of `seed` by hashing them with
+    /// FNV-1a and expanding the hash using splitmix64, as when seeding
+    /// from the time. The same string always gives the same seed.
+    pub(crate) fn new_with_string_seed(seed: &str) -> PseudoRandom {
+        PseudoRandom::new_with_hash(fnv1a(FNV_OFFSET, seed.as_bytes()))
+    }
+
+    /// Derive a seed from `key` within `namespace`, in the same way as
+    /// `new_with_string_seed()`. A byte that never appears in UTF-8
+    /// separates the two, so no other pair of strings hashes the same
+    /// bytes.
+    pub(crate) fn new_with_namespaced_seed(namespace: &str, key: &str) -> PseudoRandom {
+        let hash = fnv1a(FNV_OFFSET, namespace.as_bytes());
+        let hash = fnv1a(hash, &[0xff]);
+        PseudoRandom::new_with_hash(fnv1a(hash, key.as_bytes()))
+    }
+
+    fn new_with_hash(mut x: u64) -> PseudoRandom {
+        let mut s = [0; 4];
+        for word in s.iter_mut() {
+            *word = splitmix64(&mut x);
+        }
+        PseudoRandom { s }
+    }
+
+    /// Advance the state by 2^128 steps, as if `next_u64()` had been
+    /// called 2^128 times. Sequences separated by a jump never overlap
+    /// in practice, so they can be used by independent workers.
+    pub(crate) fn jump(&mut self) {
+        const JUMP: [u64; 4] = [
+            0x180ec6d33cfd0aba,
+            0xd5a61266f0c9392c,
+            0xa9582618e03fc9aa,
+            0x39abdc4529b1661c,
+        ];
+        self.seed_if_zero();
+        let mut s = [0; 4];
+        for jump in JUMP {
+            for b in 0..64 {
+                if jump & (1 << b) != 0 {
+                    for (s, state) in s.iter_mut().zip(self.s) {
+                        *s ^= state;
+                    }
+                }
+                self.next_u64();
+            }
+        }
+        self.s = s;
+    }
+
+    // Returns a copy of this generator and jumps this generator ahead,
+    // so the copy continues the current sequence and this generator
+    // starts a new one.
+    fn split(&mut self) -> PseudoRandom {
+        self.seed_if_zero();
+        let copy = self.clone();
+        self.jump();
+        copy
+    }
+
+    /*
     pub fn new() -> PseudoRandom {
         PseudoRandom { s: [0, 0, 0, 0] }
     }
     */
 }
 
-static RND: Mutex<PseudoRandom> = Mutex::new(PseudoRandom { s: [0, 0, 0, 0] });
+impl Rng for PseudoRandom {
+    fn next_u64(&mut self) -> u64 {
+        PseudoRandom::next_u64(self)
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        PseudoRandom::next_u32(self)
+    }
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+
+// Implement FNV-1a from http://www.isthe.com/chongo/tech/comp/fnv/
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// Implement splitmix64 from https://prng.di.unimi.it/splitmix64.c
+fn splitmix64(x: &mut u64) -> u64 {
+    *x = x.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Where random numbers are drawn from. Standalone functions
+/// use the generator owned by the current thread, a `UidStore`
+/// may own its own seeded generator or one supplied by the caller.
+/// A caller supplied generator is shared between clones.
+#[derive(Clone)]
+pub(crate) enum Source {
+    Global,
+    Seeded(PseudoRandom),
+    Custom(Arc<Mutex<dyn Rng + Send>>),
+    #[cfg(feature = "secure")]
+    Secure,
+}
+
+impl fmt::Debug for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Global => write!(f, "Global"),
+            Source::Seeded(r) => f.debug_tuple("Seeded").field(r).finish(),
+            Source::Custom(_) => write!(f, "Custom"),
+            #[cfg(feature = "secure")]
+            Source::Secure => write!(f, "Secure"),
+        }
+    }
+}
+
+impl Source {
+    /// Returns a source whose random numbers do not overlap with those
+    /// of this source. A seeded generator is split with `jump()`, the
+    /// thread's generator is split into a new seeded generator, and a
+    /// caller supplied generator is shared.
+    pub(crate) fn fork(&mut self) -> Source {
+        match self {
+            Source::Global => Source::Seeded(RND.with(|r| r.borrow_mut().split())),
+            Source::Seeded(r) => Source::Seeded(r.split()),
+            Source::Custom(r) => Source::Custom(Arc::clone(r)),
+            #[cfg(feature = "secure")]
+            Source::Secure => Source::Secure,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn next_u32(&mut self) -> u32 {
+        match self {
+            Source::Global => next_u32(),
+            Source::Seeded(r) => r.next_u32(),
+            Source::Custom(r) => r.lock().unwrap().next_u32(),
+            #[cfg(feature = "secure")]
+            Source::Secure => (secure_u64() >> 32) as u32,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        match self {
+            Source::Global => next_u64(),
+            Source::Seeded(r) => r.next_u64(),
+            Source::Custom(r) => r.lock().unwrap().next_u64(),
+            #[cfg(feature = "secure")]
+            Source::Secure => secure_u64(),
+        }
+    }
+}
+
+// Read random numbers from the operating system random number generator.
+#[cfg(feature = "secure")]
+fn secure_u64() -> u64 {
+    let mut buf = [0u8; 8];
+    getrandom::fill(&mut buf).expect("operating system random number generator failed");
+    u64::from_ne_bytes(buf)
+}
+
+// Each thread owns a generator so generation never waits on a lock.
+thread_local! {
+    static RND: RefCell<PseudoRandom> = const { RefCell::new(PseudoRandom { s: [0, 0, 0, 0] }) };
+}
 
 #[inline]
 pub(crate) fn next_u32() -> u32 {
-    RND.lock().unwrap().next_u32()
+    RND.with(|r| r.borrow_mut().next_u32())
 }
 
 #[inline]
 pub(crate) fn next_u64() -> u64 {
-    RND.lock().unwrap().next_u64()
+    RND.with(|r| r.borrow_mut().next_u64())
 }
 
 /*
 #[inline]
 pub(crate) fn next_usize() -> usize {
-    RND.lock().unwrap().next_u64() as usize
+    RND.with(|r| r.borrow_mut().next_u64() as usize)
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use super::PseudoRandom;
+
+    #[test]
+    fn test_seed_never_zero() {
+        // Besides zero, each of these produced a zero state word with
+        // the previous time based seeding.
+        let problems: [u128; 4] = [
+            0,
+            4690481050117892527,
+            9639264971936262885,
+            6412797481073129502,
+        ];
+        for now in problems {
+            for thread in [0, 1, u64::MAX] {
+                let mut r = PseudoRandom::new_with_seed([0, 0, 0, 0]);
+                r.seed_from(now, thread);
+                assert_ne!(r.s, [0, 0, 0, 0]);
+                assert!(r.s.iter().filter(|s| **s == 0).count() <= 1);
+                let first = r.next_u64();
+                assert!((0..10).any(|_| r.next_u64() != first));
+            }
+        }
+    }
+
+    #[test]
+    fn test_jump() {
+        let mut a = PseudoRandom::new_with_seed([1, 2, 3, 4]);
+        let mut b = a.clone();
+        a.jump();
+        b.jump();
+        assert_eq!(a.s, b.s);
+        assert_ne!(a.s, [1, 2, 3, 4]);
+        assert_ne!(a.s, [0, 0, 0, 0]);
+
+        let mut parent = PseudoRandom::new_with_seed([1, 2, 3, 4]);
+        let mut child = parent.split();
+        assert_eq!(child.s, [1, 2, 3, 4]);
+        assert_eq!(parent.s, a.s);
+        assert_ne!(parent.next_u64(), child.next_u64());
+
+        let mut zero = PseudoRandom::new_with_seed([0, 0, 0, 0]);
+        zero.jump();
+        assert_ne!(zero.s, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_string_seed() {
+        for seed in ["", "a", "test_name"] {
+            let mut a = PseudoRandom::new_with_string_seed(seed);
+            let mut b = PseudoRandom::new_with_string_seed(seed);
+            assert_ne!(a.s, [0, 0, 0, 0]);
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        let a = PseudoRandom::new_with_string_seed("a");
+        let b = PseudoRandom::new_with_string_seed("b");
+        assert_ne!(a.s, b.s);
+
+        let a = PseudoRandom::new_with_namespaced_seed("ab", "c");
+        let b = PseudoRandom::new_with_namespaced_seed("a", "bc");
+        assert_ne!(a.s, b.s);
+        let c = PseudoRandom::new_with_namespaced_seed("ab", "c");
+        assert_eq!(a.s, c.s);
+    }
+}
@@ -0,0 +1,100 @@
+use std::sync::{Arc, Mutex};
+
+use crate::UidStore;
+
+/// SharedUidStore is a handle to a `UidStore` that can be cloned
+/// and shared between threads. Every clone draws from the same
+/// collection of UID values, so a value is only ever generated
+/// once across all threads.
+#[derive(Debug, Clone, Default)]
+pub struct SharedUidStore {
+    store: Arc<Mutex<UidStore>>,
+}
+
+impl SharedUidStore {
+    pub fn new() -> SharedUidStore {
+        SharedUidStore::default()
+    }
+
+    /// Generate a UID string with a `length` number of characters.
+    pub fn next(&self, length: usize) -> String {
+        self.store.lock().unwrap().next(length).clone()
+    }
+
+    /// Generate a UID string that avoids commonly
+    /// confused letters such as i,I,1,L, 0,O,o.
+    pub fn next_human(&self, length: usize) -> String {
+        self.store.lock().unwrap().next_human(length).clone()
+    }
+
+    /// Register a UID with the shared `UidStore`. Returns `None` if
+    /// this string is unique and not previously seen. If the string
+    /// is already known and in use, a new uid string is returned.
+    pub fn make_unique(&self, uid: &str) -> Option<String> {
+        self.store
+            .lock()
+            .unwrap()
+            .make_unique(uid)
+            .map(|id| id.to_string())
+    }
+
+    /// Returns true if a UID is already in use.
+    pub fn contains(&self, id: &str) -> bool {
+        self.store.lock().unwrap().contains(id)
+    }
+
+    /// Returns how many UID's have already been used.
+    pub fn size(&self) -> usize {
+        self.store.lock().unwrap().size()
+    }
+}
+
+impl From<UidStore> for SharedUidStore {
+    fn from(store: UidStore) -> Self {
+        SharedUidStore {
+            store: Arc::new(Mutex::new(store)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::thread;
+
+    use crate::SharedUidStore;
+    use crate::UidStore;
+
+    #[test]
+    fn test_shared_threads() {
+        let shared = SharedUidStore::new();
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let s = shared.clone();
+                thread::spawn(move || (0..1000).map(|_| s.next(4)).collect::<Vec<_>>())
+            })
+            .collect();
+        let mut all = HashSet::new();
+        for handle in handles {
+            for id in handle.join().unwrap() {
+                assert!(all.insert(id), "duplicate uid");
+            }
+        }
+        assert_eq!(all.len(), 8000);
+        assert_eq!(shared.size(), 8000);
+    }
+
+    #[test]
+    fn test_shared_make_unique() {
+        let mut store = UidStore::new();
+        store.make_unique("abc");
+        let shared = SharedUidStore::from(store);
+        assert!(shared.contains("abc"));
+        let replaced = shared.make_unique("abc").expect("duplicate");
+        assert_eq!(replaced.len(), 3);
+        assert_ne!(replaced, "abc");
+        assert!(shared.make_unique("xyz").is_none());
+        assert_eq!(shared.next_human(6).len(), 6);
+        assert_eq!(shared.size(), 4);
+    }
+}
@@ -0,0 +1,111 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::is_valid_uid;
+
+/// A string known to be a valid base62 UID. Use this in place of a
+/// `String` so the compiler can tell UID values apart from arbitrary
+/// text. It dereferences to `str`, so it can be passed to any method
+/// that accepts a `&str`.
+///
+/// ```rust
+/// # use uid_store::*;
+/// let uid = Uid::parse("Ab3").unwrap();
+/// assert_eq!(uid.as_str(), "Ab3");
+/// assert_eq!(uid.len(), 3);
+/// assert!(Uid::parse("not-valid").is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Uid(String);
+
+impl Uid {
+    /// Returns a `Uid` if `uid` is a non-empty string containing
+    /// only base62 characters, or None otherwise.
+    pub fn parse(uid: &str) -> Option<Uid> {
+        if is_valid_uid(uid) {
+            Some(Uid(uid.to_string()))
+        } else {
+            None
+        }
+    }
+
+    // Wrap a string already known to be valid.
+    pub(crate) fn new_unchecked(uid: String) -> Uid {
+        debug_assert!(is_valid_uid(&uid));
+        Uid(uid)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the underlying `String`.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for Uid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Deref for Uid {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Uid {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Uid {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<Uid> for String {
+    fn from(uid: Uid) -> String {
+        uid.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::Uid;
+    use crate::UidStore;
+
+    #[test]
+    fn test_parse() {
+        let uid = Uid::parse("Az09").expect("valid");
+        assert_eq!(uid.as_str(), "Az09");
+        assert_eq!(uid.to_string(), "Az09");
+        assert_eq!(&*uid, "Az09");
+        assert!(uid.starts_with("Az"));
+        assert_eq!(String::from(uid.clone()), "Az09");
+        assert_eq!(uid.into_string(), "Az09");
+        assert_eq!(Uid::parse(""), None);
+        assert_eq!(Uid::parse("a b"), None);
+        assert_eq!(Uid::parse("é"), None);
+    }
+
+    #[test]
+    fn test_store() {
+        let mut u = UidStore::new();
+        let uid = u.next_uid(8);
+        assert_eq!(uid.len(), 8);
+        assert!(u.contains(&uid));
+        let set: HashSet<Uid> = [uid.clone()].into_iter().collect();
+        assert!(set.contains(uid.as_str()));
+    }
+}